@@ -0,0 +1,123 @@
+use crate::bitboard::Bitboard;
+use crate::magic;
+use crate::pieces::Color;
+use crate::square::Square;
+
+const KNIGHT_OFFSETS: [(i8, i8); 8] = [
+    (2, 1), (2, -1), (-2, 1), (-2, -1),
+    (1, 2), (1, -2), (-1, 2), (-1, -2)
+];
+
+const KING_OFFSETS: [(i8, i8); 8] = [
+    (1, 0), (-1, 0), (0, 1), (0, -1),
+    (1, 1), (-1, 1), (1, -1), (-1, -1)
+];
+
+// Ray directions as (file step, rank step). The first two of each run towards higher square
+// indices, so the nearest blocker on them is the lowest set bit; the other two run the other way.
+const ROOK_DIRECTIONS: [(i8, i8); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+const BISHOP_DIRECTIONS: [(i8, i8); 4] = [(1, 1), (-1, 1), (-1, -1), (1, -1)];
+
+const KNIGHT_ATTACKS: [Bitboard; 64] = leaper_table(&KNIGHT_OFFSETS);
+const KING_ATTACKS: [Bitboard; 64] = leaper_table(&KING_OFFSETS);
+const PAWN_ATTACKS: [[Bitboard; 64]; 2] = [
+    leaper_table(&[(-1, 1), (1, 1)]),   // White captures towards rank 8
+    leaper_table(&[(-1, -1), (1, -1)]), // Black captures towards rank 1
+];
+const ROOK_RAYS: [[Bitboard; 64]; 4] = ray_tables(&ROOK_DIRECTIONS);
+const BISHOP_RAYS: [[Bitboard; 64]; 4] = ray_tables(&BISHOP_DIRECTIONS);
+
+// For each square, the squares reached by jumping once by each offset
+const fn leaper_table(offsets: &[(i8, i8)]) -> [Bitboard; 64] {
+    let mut table = [Bitboard::EMPTY; 64];
+    let mut index = 0;
+    while index < 64 {
+        let mut attacks = 0u64;
+        let mut offset = 0;
+        while offset < offsets.len() {
+            let x = (index % 8) as i8 + offsets[offset].0;
+            let y = (index / 8) as i8 + offsets[offset].1;
+            if x >= 0 && x < 8 && y >= 0 && y < 8 {
+                attacks |= 1 << (y * 8 + x);
+            }
+            offset += 1;
+        }
+        table[index] = Bitboard(attacks);
+        index += 1;
+    }
+    table
+}
+
+// For each direction and square, every square along the ray up to the edge of the board
+const fn ray_tables(directions: &[(i8, i8); 4]) -> [[Bitboard; 64]; 4] {
+    let mut tables = [[Bitboard::EMPTY; 64]; 4];
+    let mut direction = 0;
+    while direction < 4 {
+        let mut index = 0;
+        while index < 64 {
+            let mut ray = 0u64;
+            let mut x = (index % 8) as i8 + directions[direction].0;
+            let mut y = (index / 8) as i8 + directions[direction].1;
+            while x >= 0 && x < 8 && y >= 0 && y < 8 {
+                ray |= 1 << (y * 8 + x);
+                x += directions[direction].0;
+                y += directions[direction].1;
+            }
+            tables[direction][index] = Bitboard(ray);
+            index += 1;
+        }
+        direction += 1;
+    }
+    tables
+}
+
+// Walk each ray out from the square, stopping at (and including) the first occupied square
+fn sliding_attacks(rays: &[[Bitboard; 64]; 4], square: Square, occupancy: Bitboard) -> Bitboard {
+    let mut attacks = Bitboard::EMPTY;
+    for (direction, ray_table) in rays.iter().enumerate() {
+        let ray = ray_table[square.index()];
+        let blockers = ray & occupancy;
+        let nearest_blocker = if direction < 2 { blockers.first() } else { blockers.last() };
+        attacks |= match nearest_blocker {
+            Some(blocker) => ray ^ ray_table[blocker.index()],
+            None => ray,
+        };
+    }
+    attacks
+}
+
+// The ray-walking versions the magic tables are built from
+pub(crate) fn classical_rook_attacks(square: Square, occupancy: Bitboard) -> Bitboard {
+    sliding_attacks(&ROOK_RAYS, square, occupancy)
+}
+
+pub(crate) fn classical_bishop_attacks(square: Square, occupancy: Bitboard) -> Bitboard {
+    sliding_attacks(&BISHOP_RAYS, square, occupancy)
+}
+
+pub fn knight_attacks(square: Square) -> Bitboard {
+    KNIGHT_ATTACKS[square.index()]
+}
+
+pub fn king_attacks(square: Square) -> Bitboard {
+    KING_ATTACKS[square.index()]
+}
+
+// The squares a pawn of the given color on the square could capture on
+pub fn pawn_attacks(color: Color, square: Square) -> Bitboard {
+    PAWN_ATTACKS[color as usize][square.index()]
+}
+
+// Rook moves from the square given the occupied squares, including the first blocker on each ray
+pub fn rook_attacks(square: Square, occupancy: Bitboard) -> Bitboard {
+    magic::rook_attacks(square, occupancy)
+}
+
+// Bishop moves from the square given the occupied squares, including the first blocker on each ray
+pub fn bishop_attacks(square: Square, occupancy: Bitboard) -> Bitboard {
+    magic::bishop_attacks(square, occupancy)
+}
+
+pub fn queen_attacks(square: Square, occupancy: Bitboard) -> Bitboard {
+    rook_attacks(square, occupancy) | bishop_attacks(square, occupancy)
+}
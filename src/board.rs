@@ -1,172 +1,327 @@
+use crate::attacks;
+use crate::bitboard::Bitboard;
+use crate::error::ChessError;
 use crate::pieces::{ChessPiece, Piece, Color};
+use crate::square::{File, Rank, Square};
 use crate::tile::Tile;
 
-#[derive(Debug, Clone, Copy)]
+// Bitboards for fast set operations during move generation, plus a mailbox so that looking up
+// what stands on a single square stays a plain array access. The two are always kept in sync by
+// set_piece and clear_square.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Board {
-    pub position: [Tile; 64], // Fixed-size array for the board
+    pieces: [Bitboard; 6],              // Indexed by Piece, both colors together
+    colors: [Bitboard; 2],              // Indexed by Color
+    mailbox: [Option<ChessPiece>; 64],  // Indexed by Square
 }
 
 impl Board {
-    pub fn index(x: usize, y: usize) -> usize {
-        y * 8 + x    
+    // Tiles are copies of what the board holds, so change the board through set_piece and
+    // clear_square instead
+    pub fn get_tile(&self, x: usize, y: usize) -> Tile {
+        self.tile(Square::from_coords(x, y).expect("tile coordinates must be on the board"))
     }
 
-    pub fn get_tile(&self, x: usize, y: usize) -> &Tile {
-        &self.position[Self::index(x, y)]
+    pub fn tile(&self, square: Square) -> Tile {
+        Tile::new(self.mailbox[square.index()], square)
     }
-    
-    pub fn get_tile_mut(&mut self, x: usize, y: usize) -> &mut Tile {
-        &mut self.position[Self::index(x, y)]
+
+    pub fn get_tile_with_name(&self, name: &str) -> Result<Tile, ChessError> {
+        Ok(self.tile(name.parse()?))
     }
 
-    pub fn get_tile_with_name(&self, name: &str) -> &Tile {
-        if name.len() != 2 {
-            panic!("Invalid tile name");
-        }
-    
-        let file = name.chars().next().unwrap();  // 'a' to 'h'
-        let rank = name.chars().nth(1).unwrap(); // '1' to '8'
-        let x = file as usize - 'a' as usize;
-        let y = (rank.to_digit(10).unwrap() - 1) as usize;
-        println!("x: {}, y: {}", x, y);
-    
-        if x >= 8 || y >= 8 {
-            panic!("Invalid coordinates for tile: {}", name);
-        }
-    
-        &self.position[Self::index(x, y)]
+    pub fn piece_at(&self, square: Square) -> Option<ChessPiece> {
+        self.mailbox[square.index()]
     }
 
-    pub fn get_tile_with_name_mut(&mut self, name: &str) -> &mut Tile {
-        if name.len() != 2 {
-            panic!("Invalid tile name");
-        }
-    
-        let file = name.chars().next().unwrap();  // 'a' to 'h'
-        let rank = name.chars().nth(1).unwrap(); // '1' to '8'
-        let x = file as usize - 'a' as usize;
-        let y = 8 - rank.to_digit(10).unwrap() as usize;
-    
-        if x >= 8 || y >= 8 {
-            panic!("Invalid coordinates for tile: {}", name);
-        }
-    
-        &mut self.position[Self::index(x, y)]
+    // Every tile from a1 to h8
+    pub fn tiles(&self) -> impl Iterator<Item = Tile> + '_ {
+        Square::all().map(|square| self.tile(square))
+    }
+
+    // Place a piece on the square, replacing whatever stood there
+    pub fn set_piece(&mut self, square: Square, piece: ChessPiece) {
+        self.clear_square(square);
+        self.mailbox[square.index()] = Some(piece);
+        self.pieces[piece.piece_type as usize].insert(square);
+        self.colors[piece.color as usize].insert(square);
+    }
+
+    // Empty the square, returning the piece that stood there
+    pub fn clear_square(&mut self, square: Square) -> Option<ChessPiece> {
+        let piece = self.mailbox[square.index()].take()?;
+        self.pieces[piece.piece_type as usize].remove(square);
+        self.colors[piece.color as usize].remove(square);
+        Some(piece)
+    }
+
+    pub fn pieces(&self, piece_type: Piece, color: Color) -> Bitboard {
+        self.pieces[piece_type as usize] & self.colors[color as usize]
+    }
+
+    pub fn occupancy_of(&self, color: Color) -> Bitboard {
+        self.colors[color as usize]
+    }
+
+    pub fn occupancy(&self) -> Bitboard {
+        self.colors[Color::White as usize] | self.colors[Color::Black as usize]
+    }
+
+    pub fn get_king_tile(&self, color: Color) -> Option<Tile> {
+        self.pieces(Piece::King, color).first().map(|square| self.tile(square))
+    }
+
+    // Whether any piece of the given color attacks the square
+    pub fn is_square_attacked(&self, square: Square, by: Color) -> bool {
+        !self.attackers(square, by).is_empty()
+    }
+
+    // Every tile holding a piece of the given color that attacks the square
+    pub fn attackers_of(&self, square: Square, by: Color) -> Vec<Tile> {
+        self.attackers(square, by).squares().map(|attacker| self.tile(attacker)).collect()
+    }
+
+    // The squares of every piece of the given color that attacks the square. Attacks are
+    // symmetric, so this looks outward from the square as each kind of piece would.
+    pub fn attackers(&self, square: Square, by: Color) -> Bitboard {
+        let occupancy = self.occupancy();
+        let queens = self.pieces(Piece::Queen, by);
+
+        // A pawn of `by` attacks the square if a pawn of the other color on the square would
+        // attack the pawn
+        (attacks::pawn_attacks(by.opposite(), square) & self.pieces(Piece::Pawn, by))
+            | (attacks::knight_attacks(square) & self.pieces(Piece::Knight, by))
+            | (attacks::king_attacks(square) & self.pieces(Piece::King, by))
+            | (attacks::rook_attacks(square, occupancy) & (self.pieces(Piece::Rook, by) | queens))
+            | (attacks::bishop_attacks(square, occupancy) & (self.pieces(Piece::Bishop, by) | queens))
     }
 
     pub fn is_on_board(&self, x: usize, y: usize) -> bool {
         x < 8 && y < 8
     }
 
-    // Initialize the board with empty tiles and proper names
-    pub fn init() -> Self {
-        let mut position: [Tile; 64] = [Tile::new(None, "a1"); 64];
-        
-        for y in 0..8 {
-            for x in 0..8 {
-                let file = (b'a' + x as u8) as char; // 'a' to 'h'
-                let rank = y + 1; // '1' to '8'
-                let name = format!("{}{}", file, rank); // e.g., "a8", "b8", ..., "h1"
-                position[Self::index(x, y)] = Tile::new(None, &name);
+    // Whether the given color could checkmate by any series of legal moves, which decides if
+    // running out of time loses or draws. Mates that need the opponent's pieces to block the king
+    // count, so only a lone king, a lone knight against a bare king, and bishops that all stand on
+    // the same square color as every opposing piece cannot mate.
+    pub fn has_mating_material(&self, color: Color) -> bool {
+        let own: Vec<Tile> = self.pieces_without_king(color).collect();
+        let opponent: Vec<Tile> = self.pieces_without_king(color.opposite()).collect();
+
+        let is_major_or_pawn = |tile: &Tile| {
+            matches!(tile.piece.map(|piece| piece.piece_type), Some(Piece::Pawn | Piece::Rook | Piece::Queen))
+        };
+        if own.iter().any(is_major_or_pawn) {
+            return true;
+        }
+        if own.is_empty() {
+            return false;
+        }
+
+        // Bishops only ever attack one square color, so without a blocker on the other color
+        // the mated king always has a flight square
+        let square_color = |tile: &Tile| tile.name.is_light();
+        let is_bishop = |tile: &Tile| tile.piece.is_some_and(|piece| piece.piece_type == Piece::Bishop);
+        if own.iter().all(is_bishop) && opponent.iter().all(is_bishop) {
+            let first = square_color(&own[0]);
+            if own.iter().chain(opponent.iter()).all(|tile| square_color(tile) == first) {
+                return false;
             }
         }
 
-        Self { position }
+        !(own.len() == 1 && opponent.is_empty())
     }
 
+    // Neither side has enough material left to checkmate
+    pub fn is_insufficient_material(&self) -> bool {
+        !self.has_mating_material(Color::White) && !self.has_mating_material(Color::Black)
+    }
 
-    // Create a new board with the default starting position
-    pub fn default() -> Self {
-        let mut board: Board = Self::init();
-        
-        // Set up the default starting pieces
-        for i in 0..8 {
-            board.get_tile_mut(i, 1).set_piece(ChessPiece::new(Piece::Pawn, Color::White));
-            board.get_tile_mut(i, 6).set_piece(ChessPiece::new(Piece::Pawn, Color::Black));
+    // No series of legal moves can end in checkmate. Beyond insufficient material this recognises
+    // the common blocked position where only kings and pawns remain, every pawn is jammed against
+    // an opposing pawn, and neither king can reach an undefended enemy pawn.
+    pub fn is_dead_position(&self) -> bool {
+        self.is_insufficient_material() || self.is_locked_pawn_position()
+    }
+
+    fn pieces_without_king(&self, color: Color) -> impl Iterator<Item = Tile> + '_ {
+        let pieces = self.occupancy_of(color) & !self.pieces(Piece::King, color);
+        pieces.squares().map(|square| self.tile(square))
+    }
+
+    fn is_locked_pawn_position(&self) -> bool {
+        let mut has_pawns = false;
+
+        for tile in self.tiles() {
+            let Some(piece) = tile.piece else { continue };
+            match piece.piece_type {
+                Piece::King => continue,
+                Piece::Pawn => has_pawns = true,
+                _ => return false,
+            }
+
+            // The pawn must be blocked by an enemy pawn and have nothing to capture
+            let (x, y) = tile.get_coords();
+            let ahead_y = if piece.color == Color::White { y + 1 } else { y.wrapping_sub(1) };
+            if !self.is_on_board(x, ahead_y) {
+                return false;
+            }
+            let blocker = self.get_tile(x, ahead_y).piece;
+            if !blocker.is_some_and(|blocker| blocker.piece_type == Piece::Pawn && blocker.color != piece.color) {
+                return false;
+            }
+            for capture_x in [x.wrapping_sub(1), x + 1] {
+                if !self.is_on_board(capture_x, ahead_y) {
+                    continue;
+                }
+                if self.get_tile(capture_x, ahead_y).piece.is_some_and(|target| target.color != piece.color) {
+                    return false;
+                }
+            }
         }
 
-        board.get_tile_mut(0, 0).set_piece(ChessPiece::new(Piece::Rook, Color::White));
-        board.get_tile_mut(7, 0).set_piece(ChessPiece::new(Piece::Rook, Color::White));
-        board.get_tile_mut(0, 7).set_piece(ChessPiece::new(Piece::Rook, Color::Black));
-        board.get_tile_mut(7, 7).set_piece(ChessPiece::new(Piece::Rook, Color::Black));
+        has_pawns && [Color::White, Color::Black].iter().all(|&color| !self.king_can_reach_loose_pawn(color))
+    }
+
+    // Flood fill every tile the king could walk to, ignoring the enemy king, and report whether
+    // any enemy pawn that is not defended by another pawn lies next to that region
+    fn king_can_reach_loose_pawn(&self, color: Color) -> bool {
+        let Some(king_tile) = self.get_king_tile(color) else {
+            return false;
+        };
+        let enemy = color.opposite();
+        let is_pawn = |tile: &Tile| tile.piece.is_some_and(|piece| piece.piece_type == Piece::Pawn);
+        let enemy_pawns = self.pieces(Piece::Pawn, enemy);
+        let is_pawn_attacked = |tile: &Tile| {
+            !(attacks::pawn_attacks(color, tile.name) & enemy_pawns).is_empty()
+        };
 
-        board.get_tile_mut(1, 0).set_piece(ChessPiece::new(Piece::Knight, Color::White));
-        board.get_tile_mut(6, 0).set_piece(ChessPiece::new(Piece::Knight, Color::White));
-        board.get_tile_mut(1, 7).set_piece(ChessPiece::new(Piece::Knight, Color::Black));
-        board.get_tile_mut(6, 7).set_piece(ChessPiece::new(Piece::Knight, Color::Black));
+        let mut visited = [false; 64];
+        let mut stack = vec![king_tile];
+        visited[king_tile.name.index()] = true;
 
-        board.get_tile_mut(2, 0).set_piece(ChessPiece::new(Piece::Bishop, Color::White));
-        board.get_tile_mut(5, 0).set_piece(ChessPiece::new(Piece::Bishop, Color::White));
-        board.get_tile_mut(2, 7).set_piece(ChessPiece::new(Piece::Bishop, Color::Black));
-        board.get_tile_mut(5, 7).set_piece(ChessPiece::new(Piece::Bishop, Color::Black));
+        while let Some(tile) = stack.pop() {
+            for dx in -1..=1isize {
+                for dy in -1..=1isize {
+                    let Some(square) = tile.name.offset(dx, dy) else {
+                        continue;
+                    };
+                    let neighbour = self.tile(square);
+                    let idx = square.index();
+                    if visited[idx] {
+                        continue;
+                    }
 
-        board.get_tile_mut(3, 0).set_piece(ChessPiece::new(Piece::Queen, Color::White));
-        board.get_tile_mut(3, 7).set_piece(ChessPiece::new(Piece::Queen, Color::Black));
+                    if is_pawn(&neighbour) {
+                        if neighbour.piece.is_some_and(|piece| piece.color == enemy) && !is_pawn_attacked(&neighbour) {
+                            return true;
+                        }
+                        continue;
+                    }
 
-        board.get_tile_mut(4, 0).set_piece(ChessPiece::new(Piece::King, Color::White));
-        board.get_tile_mut(4, 7).set_piece(ChessPiece::new(Piece::King, Color::Black));
-        
-        Self { position: board.position }
+                    if !is_pawn_attacked(&neighbour) {
+                        visited[idx] = true;
+                        stack.push(neighbour);
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    // Initialize an empty board
+    pub fn init() -> Self {
+        Self {
+            pieces: [Bitboard::EMPTY; 6],
+            colors: [Bitboard::EMPTY; 2],
+            mailbox: [None; 64],
+        }
     }
 
-    pub fn from_fen(fen_board: &str) -> Self {
+    pub fn from_fen(fen_board: &str) -> Result<Self, ChessError> {
         let mut board: Board = Self::init();
     
         let rows: Vec<&str> = fen_board.split('/').collect();
         if rows.len() != 8 {
-            panic!("Invalid FEN board setup");
+            return Err(ChessError::RankCount(rows.len()));
         }
     
         // Reverse the rows to match board's visual layout (from bottom to top)
         for (i, row) in rows.iter().rev().enumerate() {
+            let rank = i + 1;
             let mut col = 0;
+            let mut previous_was_digit = false;
             for c in row.chars() {
-                if c.is_digit(10) {
-                    // If the character is a number, it represents empty squares
-                    let empty_squares = c.to_digit(10).unwrap();
+                if let Some(empty_squares) = c.to_digit(10) {
+                    // A run of empty squares is a single digit from 1 to 8, never "0" or "44"
+                    if empty_squares == 0 || empty_squares > 8 || previous_was_digit {
+                        return Err(ChessError::InvalidEmptyRun { rank, character: c });
+                    }
                     col += empty_squares as usize;
+                    previous_was_digit = true;
                 } else {
+                    previous_was_digit = false;
                     // Otherwise, it's a piece
-                    let (piece, color) = match c {
-                        'r' => (Piece::Rook, Color::Black),
-                        'n' => (Piece::Knight, Color::Black),
-                        'b' => (Piece::Bishop, Color::Black),
-                        'q' => (Piece::Queen, Color::Black),
-                        'k' => (Piece::King, Color::Black),
-                        'p' => (Piece::Pawn, Color::Black),
-                        'R' => (Piece::Rook, Color::White),
-                        'N' => (Piece::Knight, Color::White),
-                        'B' => (Piece::Bishop, Color::White),
-                        'Q' => (Piece::Queen, Color::White),
-                        'K' => (Piece::King, Color::White),
-                        'P' => (Piece::Pawn, Color::White),
-                        _ => panic!("Invalid piece character in FEN"),
-                    };
+                    let piece = ChessPiece::from_fen_char(c)
+                        .ok_or(ChessError::InvalidPiece { rank, character: c })?;
+                    if col >= 8 {
+                        return Err(ChessError::RankOverflow { rank, squares: col + 1 });
+                    }
+
                     // Insert the piece into the correct position in the board
                     let x = col;
                     let y = i; // Reverse to match board's visual layout
 
-                    // Use index(x, y) to access the position in the fixed array
-                    board.get_tile_mut(x, y).set_piece(ChessPiece::new(piece, color));
+                    let square = Square::from_coords(x, y).expect("rank and file checked above");
+                    board.set_piece(square, piece);
     
                     col += 1;
                 }
             }
+
+            if col != 8 {
+                return Err(ChessError::RankOverflow { rank, squares: col });
+            }
         }
 
-        board
+        Ok(board)
     }
-    
 
+    // The FEN piece placement field, from rank 8 down to rank 1 with runs of empty tiles as digits
+    pub fn to_fen_board(&self) -> String {
+        let mut rows = Vec::with_capacity(8);
+        for y in (0..8).rev() {
+            let mut row = String::new();
+            let mut empty_squares = 0;
+            for x in 0..8 {
+                match self.get_tile(x, y).piece {
+                    Some(piece) => {
+                        if empty_squares > 0 {
+                            row.push_str(&empty_squares.to_string());
+                            empty_squares = 0;
+                        }
+                        row.push(piece.to_fen_char());
+                    }
+                    None => empty_squares += 1,
+                }
+            }
+            if empty_squares > 0 {
+                row.push_str(&empty_squares.to_string());
+            }
+            rows.push(row);
+        }
+        rows.join("/")
+    }
 
     pub fn pretty_print(&self) {
         println!("  +------------------------+");
         for y in (0..8).rev() { // Print from row 8 down to row 1
             print!("{} |", y + 1); // Row numbers
             for x in 0..8 {
-                let tile = &self.position[Self::index(x, y)];
+                let tile = self.get_tile(x, y);
                 match &tile.piece {
-                    Some(piece) => print!(" {:<2}", self.piece_to_unicode(piece)),
+                    Some(piece) => print!(" {:<2}", Board::piece_to_unicode(piece)),
                     None => print!(" . "), // Empty tile representation
                     // None => print!(" {:<5} ", tile),  // Uses the Display trait for tile name
                 }
@@ -177,7 +332,7 @@ impl Board {
         println!("    a  b  c  d  e  f  g  h"); // Column labels
     }
 
-    fn piece_to_unicode(&self, piece: &ChessPiece) -> char {
+    pub fn piece_to_unicode(piece: &ChessPiece) -> char {
         match piece.piece_type {
             Piece::King => match piece.color {
                 Color::White => '♔',
@@ -206,3 +361,101 @@ impl Board {
         }
     }
 }
+
+impl Default for Board {
+    // Create a new board with the default starting position
+    fn default() -> Self {
+        let mut board: Board = Self::init();
+
+        let back_rank = [
+            Piece::Rook, Piece::Knight, Piece::Bishop, Piece::Queen,
+            Piece::King, Piece::Bishop, Piece::Knight, Piece::Rook,
+        ];
+
+        // Set up the default starting pieces
+        for (file, piece_type) in File::ALL.into_iter().zip(back_rank) {
+            board.set_piece(Square::new(file, Rank::First), ChessPiece::new(piece_type, Color::White));
+            board.set_piece(Square::new(file, Rank::Second), ChessPiece::new(Piece::Pawn, Color::White));
+            board.set_piece(Square::new(file, Rank::Seventh), ChessPiece::new(Piece::Pawn, Color::Black));
+            board.set_piece(Square::new(file, Rank::Eighth), ChessPiece::new(piece_type, Color::Black));
+        }
+
+        board
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(placement: &str) -> Board {
+        Board::from_fen(placement).unwrap()
+    }
+
+    #[test]
+    fn bare_kings_are_insufficient() {
+        assert!(board("4k3/8/8/8/8/8/8/4K3").is_insufficient_material());
+    }
+
+    #[test]
+    fn lone_knight_cannot_mate_a_bare_king() {
+        let board = board("4k3/8/8/8/8/8/8/4KN2");
+        assert!(!board.has_mating_material(Color::White));
+        assert!(board.is_insufficient_material());
+    }
+
+    #[test]
+    fn knight_can_mate_with_the_help_of_an_enemy_piece() {
+        let board = board("4kn2/8/8/8/8/8/8/4KN2");
+        assert!(board.has_mating_material(Color::White));
+        assert!(board.has_mating_material(Color::Black));
+        assert!(!board.is_dead_position());
+    }
+
+    #[test]
+    fn two_knights_can_mate() {
+        assert!(board("4k3/8/8/8/8/8/8/4KNN1").has_mating_material(Color::White));
+    }
+
+    #[test]
+    fn bishops_on_one_square_color_cannot_mate() {
+        // c1 and f8 are both dark squares
+        assert!(board("4kb2/8/8/8/8/8/8/2B1K3").is_insufficient_material());
+    }
+
+    #[test]
+    fn bishops_on_opposite_square_colors_can_mate() {
+        // c1 is dark and c8 is light
+        let board = board("2b1k3/8/8/8/8/8/8/2B1K3");
+        assert!(board.has_mating_material(Color::White));
+        assert!(!board.is_dead_position());
+    }
+
+    #[test]
+    fn locked_pawn_chain_is_dead() {
+        let board = board("k7/8/8/p1p1p1p1/P1P1P1P1/8/8/7K");
+        assert!(!board.is_insufficient_material());
+        assert!(board.is_dead_position());
+    }
+
+    #[test]
+    fn king_reaching_a_loose_pawn_keeps_the_game_alive() {
+        // With the g-pawns gone the white king walks up the h-file to the undefended e5 pawn
+        assert!(!board("k7/8/8/p1p1p3/P1P1P3/8/8/7K").is_dead_position());
+    }
+
+    #[test]
+    fn placement_errors() {
+        assert_eq!(Board::from_fen("8/8/8/8/8/8/8").unwrap_err(), ChessError::RankCount(7));
+        assert_eq!(Board::from_fen("8/8/8/8/8/8/8/4X3").unwrap_err(), ChessError::InvalidPiece { rank: 1, character: 'X' });
+        assert_eq!(Board::from_fen("8/8/8/8/8/8/8/4K4").unwrap_err(), ChessError::RankOverflow { rank: 1, squares: 9 });
+        assert_eq!(Board::from_fen("8/8/8/8/8/8/8/4K2").unwrap_err(), ChessError::RankOverflow { rank: 1, squares: 7 });
+    }
+
+    #[test]
+    fn malformed_empty_runs() {
+        assert_eq!(Board::from_fen("8/8/8/8/8/8/8/0008").unwrap_err(), ChessError::InvalidEmptyRun { rank: 1, character: '0' });
+        assert_eq!(Board::from_fen("44/8/8/8/8/8/8/8").unwrap_err(), ChessError::InvalidEmptyRun { rank: 8, character: '4' });
+        assert_eq!(Board::from_fen("8/8/8/8/8/8/8/9").unwrap_err(), ChessError::InvalidEmptyRun { rank: 1, character: '9' });
+    }
+}
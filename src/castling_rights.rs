@@ -1,28 +1,70 @@
-#[derive(Debug, Clone, Copy)]
+use crate::board::Board;
+use crate::pieces::{Color, Piece};
+use crate::square::{File, Rank, Square};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct CastlingRights {
     pub white_king_side: bool,
     pub white_queen_side: bool,
     pub black_king_side: bool,
     pub black_queen_side: bool,
+    pub king_side_rook: [File; 2],  // Starting file of each color's king side rook, indexed by Color
+    pub queen_side_rook: [File; 2], // The same for the queen side, which differs from a/h in Chess960
 }
 
-impl CastlingRights {
-    pub fn default() -> Self {
+impl Default for CastlingRights {
+    fn default() -> Self {
         CastlingRights {
             white_king_side: true,
             white_queen_side: true,
             black_king_side: true,
             black_queen_side: true,
+            king_side_rook: [File::H; 2],
+            queen_side_rook: [File::A; 2],
         }
     }
+}
 
+impl CastlingRights {
     pub fn from_rights(rights: &str) -> Self {
         CastlingRights {
             white_king_side: rights.contains('K'),
             white_queen_side: rights.contains('Q'),
             black_king_side: rights.contains('k'),
             black_queen_side: rights.contains('q'),
+            ..CastlingRights::default()
+        }
+    }
+
+    // Read a FEN castling field against the board it belongs to. Besides KQkq this takes the
+    // Shredder-FEN and X-FEN forms of Chess960, where a file letter names the castling rook and
+    // K or Q stands for the outermost rook on that side of the king.
+    pub fn from_fen(field: &str, board: &Board) -> Self {
+        let mut rights = CastlingRights::from_rights("");
+        for right in field.chars().filter(|right| *right != '-') {
+            let color = if right.is_ascii_uppercase() { Color::White } else { Color::Black };
+            let home_rank = home_rank(color);
+            let king_file = board
+                .get_king_tile(color)
+                .map(|tile| tile.name)
+                .filter(|square| square.rank() == home_rank)
+                .map(|square| square.file())
+                .unwrap_or(File::E);
+            let mut rook_files = File::ALL.into_iter().filter(|file| {
+                board.piece_at(Square::new(*file, home_rank)).is_some_and(|piece| piece.piece_type == Piece::Rook && piece.color == color)
+            });
+
+            let (king_side, rook_file) = match right.to_ascii_lowercase() {
+                'k' => (true, rook_files.rfind(|file| *file > king_file).unwrap_or(File::H)),
+                'q' => (false, rook_files.find(|file| *file < king_file).unwrap_or(File::A)),
+                letter => match File::from_char(letter) {
+                    Some(file) => (file > king_file, file),
+                    None => continue,
+                },
+            };
+            rights.grant(color, king_side, rook_file);
         }
+        rights
     }
 
     pub fn set_castling_rights(&mut self, rights: &str) {
@@ -31,4 +73,81 @@ impl CastlingRights {
         self.black_king_side = rights.contains('k');
         self.black_queen_side = rights.contains('q');
     }
-}
\ No newline at end of file
+
+    // Whether the color may still castle on the given side
+    pub fn has_right(&self, color: Color, king_side: bool) -> bool {
+        match (color, king_side) {
+            (Color::White, true) => self.white_king_side,
+            (Color::White, false) => self.white_queen_side,
+            (Color::Black, true) => self.black_king_side,
+            (Color::Black, false) => self.black_queen_side,
+        }
+    }
+
+    // The square the rook castling on the given side starts from
+    pub fn rook_square(&self, color: Color, king_side: bool) -> Square {
+        let file = if king_side { self.king_side_rook[color as usize] } else { self.queen_side_rook[color as usize] };
+        Square::new(file, home_rank(color))
+    }
+
+    fn grant(&mut self, color: Color, king_side: bool, rook_file: File) {
+        self.set_right(color, king_side, true);
+        if king_side {
+            self.king_side_rook[color as usize] = rook_file;
+        } else {
+            self.queen_side_rook[color as usize] = rook_file;
+        }
+    }
+
+    fn set_right(&mut self, color: Color, king_side: bool, value: bool) {
+        match (color, king_side) {
+            (Color::White, true) => self.white_king_side = value,
+            (Color::White, false) => self.white_queen_side = value,
+            (Color::Black, true) => self.black_king_side = value,
+            (Color::Black, false) => self.black_queen_side = value,
+        }
+    }
+
+    // Drop both rights of a color once its king moves
+    pub fn revoke_for_color(&mut self, color: Color) {
+        self.set_right(color, true, false);
+        self.set_right(color, false, false);
+    }
+
+    // Drop the right tied to a castling rook's starting square once anything moves from or onto it
+    pub fn revoke_for_square(&mut self, square: Square) {
+        for color in [Color::White, Color::Black] {
+            for king_side in [true, false] {
+                if self.rook_square(color, king_side) == square {
+                    self.set_right(color, king_side, false);
+                }
+            }
+        }
+    }
+
+    // The FEN castling field, e.g. "KQkq", "Kq" or "-" when no rights remain. Rooks off the a and
+    // h files are written by their Shredder-FEN file letter, e.g. "GBgb".
+    pub fn to_fen_string(&self) -> String {
+        let rights: String = [(Color::White, true), (Color::White, false), (Color::Black, true), (Color::Black, false)]
+            .into_iter()
+            .filter(|(color, king_side)| self.has_right(*color, *king_side))
+            .map(|(color, king_side)| {
+                let symbol = match (king_side, self.rook_square(color, king_side).file()) {
+                    (true, File::H) => 'k',
+                    (false, File::A) => 'q',
+                    (_, file) => file.to_char(),
+                };
+                if color == Color::White { symbol.to_ascii_uppercase() } else { symbol }
+            })
+            .collect();
+
+        if rights.is_empty() { "-".to_string() } else { rights }
+    }
+}
+
+fn home_rank(color: Color) -> Rank {
+    match color {
+        Color::White => Rank::First,
+        Color::Black => Rank::Eighth,
+    }
+}
@@ -1,17 +1,47 @@
 use crate::board::Board;
 use crate::r#move::Move;
+use crate::move_list::MoveSink;
+use crate::move_stage::MoveStage;
+use crate::perft::{self, PerftStats};
+use crate::perft_cache::PerftCache;
 use crate::pieces::{ChessPiece, Color, Piece};
+use crate::san;
+use crate::language::Language;
+use crate::notation::{self, Notation};
+use crate::error::ChessError;
 use crate::fen::Fen;
+use crate::game_status::{DrawReason, GameStatus};
 use crate::castling_rights::CastlingRights;
+use crate::square::Square;
+use crate::uci;
+use crate::staged_moves::StagedMoves;
 use crate::tile::Tile;
+use crate::validation::{self, PositionViolation, ValidationMode};
+use crate::zobrist;
 
+// The state make_move overwrites, kept so unmake_move can restore it without copying the board
+#[derive(Debug, Clone, Copy)]
+pub struct MoveUndo {
+    pub mv: Move,
+    pub captured: Option<ChessPiece>,
+    pub castling_rights: CastlingRights,
+    pub en_passant_target: Option<Tile>,
+    pub halfmove_clock: u16,
+    pub fullmove_number: u16,
+    pub hash: u64,
+}
+
+#[derive(Debug, Clone)]
 pub struct Chess {
     pub board: Board,
     pub turn: Color,
     pub castling_rights: CastlingRights,
     pub en_passant_target: Option<Tile>,
-    pub halfmove_clock: u8,
-    pub fullmove_number: u8,
+    pub halfmove_clock: u16,
+    pub fullmove_number: u16,
+    hash: u64, // Zobrist hash of the current position, kept up to date by make_move and unmake_move
+    position_history: Vec<u64>, // Hash of every position reached so far, ending with the current one
+    claimed_draw: Option<DrawReason>,
 }
 
 impl Chess {
@@ -24,43 +54,116 @@ impl Chess {
             en_passant_target: None,
             fullmove_number: 1,
             halfmove_clock: 0,
+            hash: 0,
+            position_history: Vec::new(),
+            claimed_draw: None,
         }
-    }
-
-    // Create a new Chess game, starting with a default board and White to move
-    pub fn default() -> Self {
-        Chess {
-            board: Board::default(),
-            turn: Color::White,
-            castling_rights: CastlingRights::default(),
-            en_passant_target: None,
-            fullmove_number: 1,
-            halfmove_clock: 0,
-        }
+        .with_history()
     }
 
     // Create a new Chess game from a FEN string
-    pub fn from_fen(fen: &str) -> Self {
-        let fen: Fen = Fen::from_fen(fen);
+    pub fn from_fen(fen: &str) -> Result<Self, ChessError> {
+        let fen: Fen = Fen::from_fen(fen)?;
         
-        let board = Board::from_fen(&fen.board);
-        Chess {
+        let board = Board::from_fen(&fen.board)?;
+        Ok(Chess {
             board,
             turn: match &fen.turn {
                 'w' => Color::White,
                 'b' => Color::Black,
-                _ => panic!("Invalid turn color in FEN"),
+                _ => return Err(ChessError::InvalidSideToMove(fen.turn.to_string())),
             },
-            castling_rights: CastlingRights::from_rights(&fen.castling),
+            castling_rights: CastlingRights::from_fen(&fen.castling, &board),
             en_passant_target: match fen.en_passant.as_str() {
                 "-" => None,
-                _ => Some(*board.get_tile_with_name(&fen.en_passant)),
+                _ => Some(board.tile(fen.en_passant.parse()?)),
             },
             halfmove_clock: fen.halfmove_clock,
             fullmove_number: fen.fullmove_number,
+            hash: 0,
+            position_history: Vec::new(),
+            claimed_draw: None,
+        }
+        .with_history())
+    }
+
+    // Create a new Chess game from a FEN string, also rejecting positions that could not arise in
+    // a real game. Lenient mode first drops castling rights and en passant targets that do not fit.
+    pub fn from_fen_validated(fen: &str, mode: ValidationMode) -> Result<Self, ChessError> {
+        let mut chess = Chess::from_fen(fen)?;
+
+        let mut violations = chess.validate();
+        if mode == ValidationMode::Lenient {
+            chess.repair(&violations);
+            violations.retain(|violation| !violation.is_repairable());
+        }
+
+        if violations.is_empty() {
+            Ok(chess)
+        } else {
+            Err(ChessError::InvalidPosition(violations))
         }
     }
 
+    // Every way the current position breaks the rules of chess
+    pub fn validate(&self) -> Vec<PositionViolation> {
+        validation::find_violations(self)
+    }
+
+    fn repair(&mut self, violations: &[PositionViolation]) {
+        for violation in violations {
+            match violation {
+                PositionViolation::InvalidEnPassant(_) => self.en_passant_target = None,
+                PositionViolation::InvalidCastlingRight(right) => match right {
+                    'K' => self.castling_rights.white_king_side = false,
+                    'Q' => self.castling_rights.white_queen_side = false,
+                    'k' => self.castling_rights.black_king_side = false,
+                    'q' => self.castling_rights.black_queen_side = false,
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+        self.hash = self.compute_hash();
+        self.position_history = vec![self.hash];
+    }
+
+    // Hash the current position and start the repetition history from it
+    fn with_history(mut self) -> Self {
+        self.hash = self.compute_hash();
+        self.position_history = vec![self.hash];
+        self
+    }
+
+    // Zobrist hash of the current position, covering the pieces, side to move, castling rights
+    // and en passant file. Equal positions under the repetition rules have equal hashes.
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    // Hash the current position from scratch rather than reading the incrementally kept hash
+    pub fn compute_hash(&self) -> u64 {
+        zobrist::hash(&self.board, self.turn, self.castling_rights, self.en_passant_target)
+    }
+
+    // Describe the current position as a FEN string that from_fen reads back to the same game
+    pub fn to_fen(&self) -> String {
+        Fen {
+            board: self.board.to_fen_board(),
+            turn: match self.turn {
+                Color::White => 'w',
+                Color::Black => 'b',
+            },
+            castling: self.castling_rights.to_fen_string(),
+            en_passant: self
+                .en_passant_target
+                .map_or_else(|| "-".to_string(), |target| target.name.to_string()),
+            halfmove_clock: self.halfmove_clock,
+            fullmove_number: self.fullmove_number,
+        }
+        .to_fen()
+    }
+
     pub fn get_turn(&self) -> Color {
         self.turn
     }
@@ -68,43 +171,453 @@ impl Chess {
 
     pub fn switch_turn(&mut self) {
         // Switch to the other player's turn
-        self.turn = match self.turn {
-            Color::White => Color::Black,
-            Color::Black => Color::White,
-        };
+        self.turn = self.turn.opposite();
+        self.hash ^= zobrist::black_to_move();
     }
 
+    // Every move the side to move's pieces can make, including ones that leave its king in check
+    pub fn get_pseudo_legal_moves(&self) -> Vec<Move> {
+        let mut moves = Vec::new();
+        self.generate_pseudo_legal_moves(MoveStage::All, &mut moves);
+        moves
+    }
+
+    // Pseudo-legal moves filtered down to those that do not leave the mover's king attacked
     pub fn get_legal_moves(&self) -> Vec<Move> {
-        let mut legal_moves = Vec::new();
+        let mut moves = Vec::new();
+        self.generate_legal_moves(MoveStage::All, &mut moves);
+        moves
+    }
 
-        // Iterate over all tiles and find pieces of the correct color
-        for tile in self.board.position.iter() {
+    // Push the pseudo-legal moves of the given stage onto the list
+    pub fn generate_pseudo_legal_moves(&self, stage: MoveStage, moves: &mut impl MoveSink) {
+        // Visit every square holding a piece of the side to move
+        for square in self.board.occupancy_of(self.turn) {
+            let tile = self.board.tile(square);
             if let Some(piece) = &tile.piece {
-                if piece.color == self.turn {
-                    // Get possible moves for the piece on this tile
-                    let possible_moves = piece.get_possible_moves(
-                        *tile, 
-                        &self.board, 
-                        self.castling_rights,
-                        self.en_passant_target,
-                    );
-                    
-                    // let possible_moves = piece.get_possible_moves(self, *piece, tile);
-                    legal_moves.extend(possible_moves);
-                }
+                piece.generate_moves(tile, &self.board, self.castling_rights, self.en_passant_target, stage, moves);
             }
         }
-        legal_moves
     }
 
-    fn is_valid_square(&self, x: usize, y: usize) -> bool {
-        // Ensure that the square is within bounds
-        x < 8 && y < 8
+    // Push the legal moves of the given stage onto the list
+    pub fn generate_legal_moves(&self, stage: MoveStage, moves: &mut impl MoveSink) {
+        let start = moves.len();
+        self.generate_pseudo_legal_moves(stage, moves);
+
+        // Only filter the moves generated here, leaving anything already in the list alone
+        let mut index = 0;
+        moves.retain(|mv| {
+            index += 1;
+            index <= start || self.is_legal(mv)
+        });
+    }
+
+    // Legal moves, captures first and quiet moves after, generated one stage at a time
+    pub fn staged_moves(&self) -> StagedMoves<'_> {
+        StagedMoves::new(self)
+    }
+
+    // Whether a pseudo-legal move keeps the mover's king out of check. The move is played on a
+    // copy of the board alone, so no history or other game state is touched.
+    pub fn is_legal(&self, mv: &Move) -> bool {
+        let mut board = self.board;
+        Self::move_pieces(&mut board, mv, self.en_passant_target);
+        match board.get_king_tile(self.turn) {
+            Some(king_tile) => !board.is_square_attacked(king_tile.name, self.turn.opposite()),
+            None => true,
+        }
+    }
+
+    // Whether the given color's king is attacked by the opposing side
+    fn is_king_attacked(&self, color: Color) -> bool {
+        let Some(king_tile) = self.board.get_king_tile(color) else {
+            return false;
+        };
+        self.board.is_square_attacked(king_tile.name, color.opposite())
+    }
+
+    // Play a move on the board and update turn, castling rights, en passant target and clocks.
+    // Returns the undo record needed to take the move back with unmake_move.
+    pub fn make_move(&mut self, mv: Move) -> MoveUndo {
+        let from = mv.from.name;
+        let to = mv.to.name;
+        let is_pawn = mv.piece.piece_type == Piece::Pawn;
+
+        // Whether the old en passant target counts towards the hash depends on the pawns around it
+        // before they move
+        let old_en_passant_hash = zobrist::en_passant_target(&self.board, self.turn, self.en_passant_target);
+
+        let captured = Self::move_pieces(&mut self.board, &mv, self.en_passant_target);
+        let undo = MoveUndo {
+            mv,
+            captured,
+            castling_rights: self.castling_rights,
+            en_passant_target: self.en_passant_target,
+            halfmove_clock: self.halfmove_clock,
+            fullmove_number: self.fullmove_number,
+            hash: self.hash,
+        };
+
+        // Take the moved, captured and castled pieces out of the hash at their old squares and
+        // put them back in at their new ones, along with the rights and target about to change
+        self.hash ^= zobrist::piece(mv.piece, from) ^ zobrist::piece(mv.promotion.unwrap_or(mv.piece), to);
+        if let Some(captured) = captured {
+            let captured_square = if Self::is_en_passant(&mv, self.en_passant_target) { Square::new(to.file(), from.rank()) } else { to };
+            self.hash ^= zobrist::piece(captured, captured_square);
+        }
+        if let Some((rook_from, rook_to)) = mv.castling_rook_squares() {
+            let rook = ChessPiece::new(Piece::Rook, mv.piece.color);
+            self.hash ^= zobrist::piece(rook, rook_from) ^ zobrist::piece(rook, rook_to);
+        }
+        self.hash ^= zobrist::castling(self.castling_rights);
+        self.hash ^= old_en_passant_hash;
+
+        // Moving the king or a rook, or capturing a rook on its starting square, loses those rights
+        if mv.piece.piece_type == Piece::King {
+            self.castling_rights.revoke_for_color(mv.piece.color);
+        }
+        self.castling_rights.revoke_for_square(from);
+        self.castling_rights.revoke_for_square(to);
+
+        // A double pawn push leaves the skipped tile as the en passant target
+        let (from_y, to_y) = (from.rank().index(), to.rank().index());
+        self.en_passant_target = if is_pawn && from_y.abs_diff(to_y) == 2 {
+            Some(self.board.get_tile(from.file().index(), (from_y + to_y) / 2))
+        } else {
+            None
+        };
+
+        if is_pawn || captured.is_some() {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock = self.halfmove_clock.saturating_add(1);
+        }
+
+        if self.turn == Color::Black {
+            self.fullmove_number = self.fullmove_number.saturating_add(1);
+        }
+
+        // A claimed draw belongs to the position it was claimed in, playing on abandons it
+        self.claimed_draw = None;
+        self.switch_turn();
+        self.hash ^= zobrist::castling(self.castling_rights);
+        self.hash ^= zobrist::en_passant_target(&self.board, self.turn, self.en_passant_target);
+        self.position_history.push(self.hash);
+
+        undo
     }
 
-    fn index_to_coords(&self, index: usize) -> (usize, usize) {
-        (index % 8, index / 8) // Convert index to x, y coordinates
+    // Move the pieces a move touches on the board and return the piece it captured
+    fn move_pieces(board: &mut Board, mv: &Move, en_passant_target: Option<Tile>) -> Option<ChessPiece> {
+        let from = mv.from.name;
+        let to = mv.to.name;
+
+        // In Chess960 the king and rook can land on each other's squares, so lift both before
+        // putting either down
+        if let Some((rook_from, rook_to)) = mv.castling_rook_squares() {
+            let rook = board.clear_square(rook_from);
+            board.clear_square(from);
+            if let Some(rook) = rook {
+                board.set_piece(rook_to, rook);
+            }
+            board.set_piece(to, mv.piece);
+            return None;
+        }
+
+        // En passant captures a pawn that sits beside the origin, not on the destination tile
+        let captured = if Self::is_en_passant(mv, en_passant_target) {
+            board.clear_square(Square::new(to.file(), from.rank()))
+        } else {
+            board.clear_square(to)
+        };
+
+        board.clear_square(from);
+        board.set_piece(to, mv.promotion.unwrap_or(mv.piece));
+        captured
+    }
+
+    // Take back a move played with make_move, restoring the exact prior state
+    pub fn unmake_move(&mut self, undo: MoveUndo) {
+        let mv = undo.mv;
+        let from = mv.from.name;
+        let to = mv.to.name;
+
+        self.position_history.pop();
+        self.claimed_draw = None;
+        self.switch_turn();
+
+        if let Some((rook_from, rook_to)) = mv.castling_rook_squares() {
+            let rook = self.board.clear_square(rook_to);
+            self.board.clear_square(to);
+            if let Some(rook) = rook {
+                self.board.set_piece(rook_from, rook);
+            }
+        } else {
+            self.board.clear_square(to);
+        }
+        self.board.set_piece(from, mv.piece);
+
+        // Put back whatever was captured, beside the destination for en passant
+        if let Some(captured) = undo.captured {
+            let captured_square = if Self::is_en_passant(&mv, undo.en_passant_target) { Square::new(to.file(), from.rank()) } else { to };
+            self.board.set_piece(captured_square, captured);
+        }
+
+        self.castling_rights = undo.castling_rights;
+        self.en_passant_target = undo.en_passant_target;
+        self.halfmove_clock = undo.halfmove_clock;
+        self.fullmove_number = undo.fullmove_number;
+        self.hash = undo.hash;
     }
 
-    // Additional methods for game state management (check, checkmate, win condition, etc.)
+    // A pawn moving onto the en passant target can only be an en passant capture
+    fn is_en_passant(mv: &Move, en_passant_target: Option<Tile>) -> bool {
+        mv.piece.piece_type == Piece::Pawn && en_passant_target.is_some_and(|target| target.name == mv.to.name)
+    }
+
+
+    // Write a legal move of the current position in Standard Algebraic Notation
+    pub fn move_to_san(&self, mv: &Move) -> String {
+        san::move_to_san(self, mv)
+    }
+
+    // Find the legal move a SAN string such as "Nf3", "exd6 e.p." or "O-O" stands for
+    pub fn parse_san(&self, text: &str) -> Result<Move, ChessError> {
+        san::parse_san(self, text)
+    }
+
+    // Find the legal move a UCI string such as "e2e4", "e7e8q", "e1g1" or "e1h1" stands for
+    pub fn parse_uci(&self, text: &str) -> Result<Move, ChessError> {
+        uci::parse_uci(self, text)
+    }
+
+    // Write a legal move of the current position in figurine, localized, long algebraic,
+    // descriptive or ICCF numeric notation
+    pub fn move_to_notation(&self, mv: &Move, notation: Notation) -> String {
+        notation::write_move(self, mv, notation)
+    }
+
+    // Find the legal move SAN with another language's piece letters, or with figurines, stands for
+    pub fn parse_localized_san(&self, text: &str, language: Language) -> Result<Move, ChessError> {
+        notation::parse_localized_san(self, text, language)
+    }
+
+    pub fn parse_iccf(&self, text: &str) -> Result<Move, ChessError> {
+        notation::parse_iccf(self, text)
+    }
+
+    // Count the leaves of the legal move tree to the given depth, with the standard breakdown
+    // of the moves played on the last ply
+    pub fn perft(&self, depth: u32) -> PerftStats {
+        perft::perft(&mut self.clone(), depth)
+    }
+
+    // Perft for each legal move in the current position, for tracking down generator bugs
+    pub fn perft_divide(&self, depth: u32) -> Vec<(Move, PerftStats)> {
+        perft::perft_divide(&mut self.clone(), depth)
+    }
+
+    // Perft with the root moves split across threads (0 for one per core), optionally reusing
+    // subtree results from a cache. Gives exactly the same counts as perft.
+    pub fn perft_parallel(&self, depth: u32, threads: usize, cache: Option<&PerftCache>) -> PerftStats {
+        perft::perft_parallel(self, depth, threads, cache)
+    }
+
+    pub fn perft_divide_parallel(&self, depth: u32, threads: usize, cache: Option<&PerftCache>) -> Vec<(Move, PerftStats)> {
+        perft::perft_divide_parallel(self, depth, threads, cache)
+    }
+
+    // Whether the side to move is in check
+    pub fn is_in_check(&self) -> bool {
+        self.is_king_attacked(self.turn)
+    }
+
+    // The side to move is in check and has no legal move to escape it
+    pub fn is_checkmate(&self) -> bool {
+        self.is_in_check() && self.get_legal_moves().is_empty()
+    }
+
+    // The side to move is not in check but has no legal move
+    pub fn is_stalemate(&self) -> bool {
+        !self.is_in_check() && self.get_legal_moves().is_empty()
+    }
+
+    // How many times the current position has occurred, counting itself. Only positions since the
+    // last capture or pawn move can repeat, so the search stops at the halfmove clock.
+    pub fn repetition_count(&self) -> usize {
+        let current = self.position_history.last();
+        self.position_history
+            .iter()
+            .rev()
+            .take(self.halfmove_clock as usize + 1)
+            .filter(|key| Some(*key) == current)
+            .count()
+    }
+
+    // The draw the side to move could claim right now under the fifty-move or threefold rules
+    fn claimable_draw(&self) -> Option<DrawReason> {
+        if self.repetition_count() >= 3 {
+            Some(DrawReason::ThreefoldRepetition)
+        } else if self.halfmove_clock >= 100 {
+            Some(DrawReason::FiftyMoveRule)
+        } else {
+            None
+        }
+    }
+
+    pub fn can_claim_draw(&self) -> bool {
+        self.claimable_draw().is_some()
+    }
+
+    // Claim a draw on behalf of the side to move. Once accepted the game status reports the draw
+    // until another move is played.
+    pub fn claim_draw(&mut self) -> Option<DrawReason> {
+        if self.status() != GameStatus::Ongoing {
+            return None;
+        }
+        self.claimed_draw = self.claimable_draw();
+        self.claimed_draw
+    }
+
+    // Whether the game has ended, and how
+    pub fn status(&self) -> GameStatus {
+        if self.get_legal_moves().is_empty() {
+            return if self.is_in_check() {
+                GameStatus::Checkmate { winner: self.turn.opposite() }
+            } else {
+                GameStatus::Stalemate
+            };
+        }
+
+        // These end the game automatically, without either player claiming them
+        if self.board.is_insufficient_material() {
+            return GameStatus::Draw { reason: DrawReason::InsufficientMaterial };
+        }
+        if self.board.is_dead_position() {
+            return GameStatus::Draw { reason: DrawReason::DeadPosition };
+        }
+        if self.halfmove_clock >= 150 {
+            return GameStatus::Draw { reason: DrawReason::SeventyFiveMoveRule };
+        }
+        if self.repetition_count() >= 5 {
+            return GameStatus::Draw { reason: DrawReason::FivefoldRepetition };
+        }
+
+        if let Some(reason) = self.claimed_draw {
+            return GameStatus::Draw { reason };
+        }
+
+        GameStatus::Ongoing
+    }
+}
+
+impl Default for Chess {
+    // Create a new Chess game, starting with a default board and White to move
+    fn default() -> Self {
+        Chess {
+            board: Board::default(),
+            turn: Color::White,
+            castling_rights: CastlingRights::default(),
+            en_passant_target: None,
+            fullmove_number: 1,
+            halfmove_clock: 0,
+            hash: 0,
+            position_history: Vec::new(),
+            claimed_draw: None,
+        }
+        .with_history()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::move_list::MAX_MOVES;
+
+    #[test]
+    fn unmake_restores_a_saturated_fullmove_number() {
+        let mut chess = Chess::from_fen("4k3/8/8/8/8/8/8/4K3 b - - 0 65535").unwrap();
+        let before = chess.to_fen();
+        let mv = chess.parse_uci("e8d8").unwrap();
+        let undo = chess.make_move(mv);
+        assert_eq!(chess.fullmove_number, 65535);
+        chess.unmake_move(undo);
+        assert_eq!(chess.to_fen(), before);
+    }
+
+    fn play(chess: &mut Chess, moves: &str) {
+        for san in moves.split_whitespace() {
+            let mv = chess.parse_san(san).unwrap();
+            chess.make_move(mv);
+        }
+    }
+
+    #[test]
+    fn repetition_allows_a_claim_at_three_and_ends_the_game_at_five() {
+        let mut chess = Chess::default();
+        play(&mut chess, "Nf3 Nf6 Ng1 Ng8");
+        assert_eq!(chess.repetition_count(), 2);
+        assert!(!chess.can_claim_draw());
+
+        play(&mut chess, "Nf3 Nf6 Ng1 Ng8");
+        assert_eq!(chess.repetition_count(), 3);
+        assert!(chess.can_claim_draw());
+        assert_eq!(chess.status(), GameStatus::Ongoing);
+
+        play(&mut chess, "Nf3 Nf6 Ng1 Ng8 Nf3 Nf6 Ng1 Ng8");
+        assert_eq!(chess.repetition_count(), 5);
+        assert_eq!(chess.status(), GameStatus::Draw { reason: DrawReason::FivefoldRepetition });
+    }
+
+    #[test]
+    fn claimed_repetition_draw() {
+        let mut chess = Chess::default();
+        assert_eq!(chess.claim_draw(), None);
+        play(&mut chess, "Nf3 Nf6 Ng1 Ng8 Nf3 Nf6 Ng1 Ng8");
+        assert_eq!(chess.claim_draw(), Some(DrawReason::ThreefoldRepetition));
+        assert_eq!(chess.status(), GameStatus::Draw { reason: DrawReason::ThreefoldRepetition });
+    }
+
+    #[test]
+    fn fifty_move_rule_is_claimable_from_a_clock_of_100() {
+        let mut chess = Chess::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 99 80").unwrap();
+        assert!(!chess.can_claim_draw());
+        assert_eq!(chess.claim_draw(), None);
+
+        play(&mut chess, "Ra2");
+        assert_eq!(chess.halfmove_clock, 100);
+        assert!(chess.can_claim_draw());
+        assert_eq!(chess.status(), GameStatus::Ongoing);
+        assert_eq!(chess.claim_draw(), Some(DrawReason::FiftyMoveRule));
+        assert_eq!(chess.status(), GameStatus::Draw { reason: DrawReason::FiftyMoveRule });
+    }
+
+    #[test]
+    fn seventy_five_move_rule_ends_the_game_at_150() {
+        let chess = Chess::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 149 80").unwrap();
+        assert_eq!(chess.status(), GameStatus::Ongoing);
+        let chess = Chess::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 150 80").unwrap();
+        assert_eq!(chess.status(), GameStatus::Draw { reason: DrawReason::SeventyFiveMoveRule });
+    }
+
+    #[test]
+    fn playing_on_abandons_a_claimed_draw() {
+        let mut chess = Chess::from_fen("4k3/8/8/8/8/8/4P3/R3K3 w - - 100 80").unwrap();
+        assert_eq!(chess.claim_draw(), Some(DrawReason::FiftyMoveRule));
+        play(&mut chess, "e4");
+        assert_eq!(chess.status(), GameStatus::Ongoing);
+        assert!(!chess.can_claim_draw());
+    }
+
+    #[test]
+    fn positions_with_more_moves_than_a_move_list_holds() {
+        let chess = Chess::from_fen("QQQQQQQQ/Q6Q/Q6Q/Q6Q/Q6Q/Q6Q/Q6Q/kQQQQQQK w - - 0 1").unwrap();
+        assert!(chess.get_pseudo_legal_moves().len() > MAX_MOVES);
+        assert!(chess.get_legal_moves().len() > MAX_MOVES);
+        // The fixed-size lists behind staged generation and perft stop at their capacity
+        assert!(chess.staged_moves().count() <= chess.get_legal_moves().len());
+        assert!(chess.perft(1).nodes <= MAX_MOVES as u64);
+        assert_eq!(chess.status(), GameStatus::Ongoing);
+    }
 }
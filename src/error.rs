@@ -0,0 +1,85 @@
+use std::fmt;
+
+use crate::validation::PositionViolation;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChessError {
+    FieldCount(usize),                            // FEN needs exactly 6 whitespace-separated fields
+    RankCount(usize),                             // FEN placement needs exactly 8 ranks
+    InvalidPiece { rank: usize, character: char }, // Unknown character in a placement rank
+    InvalidEmptyRun { rank: usize, character: char }, // A "0", a "9" or a digit straight after another
+    RankOverflow { rank: usize, squares: usize },  // A placement rank does not describe 8 squares
+    InvalidSideToMove(String),
+    InvalidCastling(String),
+    InvalidEnPassant(String),
+    InvalidHalfmoveClock(String),
+    InvalidFullmoveNumber(String),
+    InvalidSquare(String),                        // Not a tile name in "a1".."h8"
+    InvalidPosition(Vec<PositionViolation>),      // Parses, but could not arise in a real game
+    InvalidMoveNotation(String),                  // Not a move in any notation we read
+    IllegalMove(String),                          // Reads as a move, but no legal move matches it
+    AmbiguousMove(String),                        // More than one legal move matches it
+    InvalidPgn { line: usize, column: usize, reason: String },
+    InvalidPgnMove { line: usize, column: usize, text: String, error: Box<ChessError> }, // A move that does not replay
+}
+
+impl fmt::Display for ChessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChessError::FieldCount(found) => {
+                write!(f, "FEN must have 6 fields, found {}", found)
+            }
+            ChessError::RankCount(found) => {
+                write!(f, "FEN piece placement must have 8 ranks, found {}", found)
+            }
+            ChessError::InvalidPiece { rank, character } => {
+                write!(f, "invalid piece character '{}' in rank {} of FEN piece placement", character, rank)
+            }
+            ChessError::InvalidEmptyRun { rank, character } => {
+                write!(f, "invalid empty square count '{}' in rank {} of FEN piece placement, expected a single digit from 1 to 8", character, rank)
+            }
+            ChessError::RankOverflow { rank, squares } => {
+                write!(f, "rank {} of FEN piece placement describes {} squares instead of 8", rank, squares)
+            }
+            ChessError::InvalidSideToMove(field) => {
+                write!(f, "invalid side to move '{}' in FEN, expected 'w' or 'b'", field)
+            }
+            ChessError::InvalidCastling(field) => {
+                write!(f, "invalid castling rights '{}' in FEN, expected '-' or a combination of 'KQkq' or castling rook files", field)
+            }
+            ChessError::InvalidEnPassant(field) => {
+                write!(f, "invalid en passant square '{}' in FEN, expected '-' or a square on rank 6 with White to move or rank 3 with Black to move", field)
+            }
+            ChessError::InvalidHalfmoveClock(field) => {
+                write!(f, "invalid halfmove clock '{}' in FEN", field)
+            }
+            ChessError::InvalidFullmoveNumber(field) => {
+                write!(f, "invalid fullmove number '{}' in FEN, expected a number from 1", field)
+            }
+            ChessError::InvalidSquare(name) => {
+                write!(f, "invalid square '{}', expected a file a-h followed by a rank 1-8", name)
+            }
+            ChessError::InvalidPosition(violations) => {
+                let violations: Vec<String> = violations.iter().map(|violation| violation.to_string()).collect();
+                write!(f, "invalid position: {}", violations.join("; "))
+            }
+            ChessError::InvalidMoveNotation(text) => {
+                write!(f, "'{}' is not a move in algebraic notation", text)
+            }
+            ChessError::IllegalMove(text) => {
+                write!(f, "'{}' is not a legal move in this position", text)
+            }
+            ChessError::AmbiguousMove(text) => {
+                write!(f, "'{}' matches more than one legal move", text)
+            }
+            ChessError::InvalidPgn { line, column, reason } => {
+                write!(f, "invalid PGN at line {}, column {}: {}", line, column, reason)
+            }
+            ChessError::InvalidPgnMove { line, column, text, error } => {
+                write!(f, "cannot play move '{}' at line {}, column {}: {}", text, line, column, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ChessError {}
@@ -1,36 +1,78 @@
+use crate::error::ChessError;
+use crate::square::{Rank, Square};
 
 #[derive(Debug, Clone)]
 pub struct Fen {
     pub board: String,        // The board setup (like "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR")
     pub turn: char,           // Whose turn it is ('w' for white, 'b' for black)
-    pub castling: String,     // Castling rights ('KQkq' or empty string if no rights)
+    pub castling: String,     // Castling rights ('KQkq', Shredder-FEN files like 'HAha', or '-' if no rights)
     pub en_passant: String,   // En passant target square (or '-' if none)
-    pub halfmove_clock: u8,   // Halfmove clock (for fifty-move rule)
-    pub fullmove_number: u8,  // Fullmove number (starts at 1)
+    pub halfmove_clock: u16,  // Halfmove clock (for fifty-move rule)
+    pub fullmove_number: u16, // Fullmove number (starts at 1)
 }
 
 impl Fen {
-    pub fn from_fen(fen: &str) -> Self {
+    pub fn from_fen(fen: &str) -> Result<Self, ChessError> {
         let parts: Vec<&str> = fen.split_whitespace().collect();
         if parts.len() != 6 {
-            panic!("Invalid FEN string, expected 6 parts");
+            return Err(ChessError::FieldCount(parts.len()));
         }
 
         let board = parts[0].to_string();
-        let turn = parts[1].chars().next().unwrap();
+
+        let turn = match parts[1] {
+            "w" => 'w',
+            "b" => 'b',
+            other => return Err(ChessError::InvalidSideToMove(other.to_string())),
+        };
+
+        // Either "-" or up to four distinct rights, each K, Q, k, q or, for Chess960, the file
+        // letter of the castling rook: uppercase for White and lowercase for Black
         let castling = parts[2].to_string();
+        if castling != "-" {
+            let mut seen = Vec::new();
+            let is_valid = (1..=4).contains(&castling.len())
+                && castling.chars().all(|right| {
+                    let is_right = "KQkqABCDEFGHabcdefgh".contains(right) && !seen.contains(&right);
+                    seen.push(right);
+                    is_right
+                });
+            if !is_valid {
+                return Err(ChessError::InvalidCastling(castling));
+            }
+        }
+
+        // Either "-" or the square the opponent's pawn just skipped over: on the sixth rank when
+        // White is to move and on the third when Black is
         let en_passant = parts[3].to_string();
-        let halfmove_clock = parts[4].parse().unwrap_or(0);
-        let fullmove_number = parts[5].parse().unwrap_or(1);
+        if en_passant != "-" {
+            let target_rank = if turn == 'w' { Rank::Sixth } else { Rank::Third };
+            let is_valid = en_passant
+                .parse::<Square>()
+                .is_ok_and(|square| square.rank() == target_rank);
+            if !is_valid {
+                return Err(ChessError::InvalidEnPassant(en_passant));
+            }
+        }
+
+        let halfmove_clock = parts[4]
+            .parse()
+            .map_err(|_| ChessError::InvalidHalfmoveClock(parts[4].to_string()))?;
 
-        Fen {
+        let fullmove_number = parts[5]
+            .parse()
+            .ok()
+            .filter(|&number: &u16| number >= 1)
+            .ok_or_else(|| ChessError::InvalidFullmoveNumber(parts[5].to_string()))?;
+
+        Ok(Fen {
             board,
             turn,
             castling,
             en_passant,
             halfmove_clock,
             fullmove_number,
-        }
+        })
     }
 
     pub fn to_fen(&self) -> String {
@@ -44,4 +86,51 @@ impl Fen {
             self.fullmove_number
         )
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(fen: &str) -> ChessError {
+        Fen::from_fen(fen).unwrap_err()
+    }
+
+    const PLACEMENT: &str = "4k3/8/8/8/8/8/8/4K3";
+
+    #[test]
+    fn field_count() {
+        assert_eq!(error(&format!("{} w - - 0", PLACEMENT)), ChessError::FieldCount(5));
+        assert_eq!(error(&format!("{} w - - 0 1 extra", PLACEMENT)), ChessError::FieldCount(7));
+    }
+
+    #[test]
+    fn side_to_move() {
+        assert_eq!(error(&format!("{} W - - 0 1", PLACEMENT)), ChessError::InvalidSideToMove(String::from("W")));
+    }
+
+    #[test]
+    fn castling() {
+        assert_eq!(error(&format!("{} w KK - 0 1", PLACEMENT)), ChessError::InvalidCastling(String::from("KK")));
+        assert_eq!(error(&format!("{} w KQkqA - 0 1", PLACEMENT)), ChessError::InvalidCastling(String::from("KQkqA")));
+        assert_eq!(error(&format!("{} w X - 0 1", PLACEMENT)), ChessError::InvalidCastling(String::from("X")));
+        assert!(Fen::from_fen(&format!("{} w HAha - 0 1", PLACEMENT)).is_ok());
+    }
+
+    #[test]
+    fn en_passant_rank_follows_the_side_to_move() {
+        assert_eq!(error(&format!("{} w - e3 0 1", PLACEMENT)), ChessError::InvalidEnPassant(String::from("e3")));
+        assert_eq!(error(&format!("{} b - e6 0 1", PLACEMENT)), ChessError::InvalidEnPassant(String::from("e6")));
+        assert_eq!(error(&format!("{} w - e9 0 1", PLACEMENT)), ChessError::InvalidEnPassant(String::from("e9")));
+        assert!(Fen::from_fen(&format!("{} w - e6 0 1", PLACEMENT)).is_ok());
+        assert!(Fen::from_fen(&format!("{} b - e3 0 1", PLACEMENT)).is_ok());
+    }
+
+    #[test]
+    fn clocks() {
+        assert_eq!(error(&format!("{} w - - x 1", PLACEMENT)), ChessError::InvalidHalfmoveClock(String::from("x")));
+        assert_eq!(error(&format!("{} w - - 65536 1", PLACEMENT)), ChessError::InvalidHalfmoveClock(String::from("65536")));
+        assert_eq!(error(&format!("{} w - - 0 0", PLACEMENT)), ChessError::InvalidFullmoveNumber(String::from("0")));
+        assert_eq!(error(&format!("{} w - - 0 -1", PLACEMENT)), ChessError::InvalidFullmoveNumber(String::from("-1")));
+    }
+}
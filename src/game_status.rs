@@ -0,0 +1,19 @@
+use crate::pieces::Color;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawReason {
+    FiftyMoveRule,        // Claimed after 50 moves by each side without a capture or pawn move
+    SeventyFiveMoveRule,  // 75 moves by each side without a capture or pawn move
+    ThreefoldRepetition,  // Claimed once the same position has occurred three times
+    FivefoldRepetition,   // The same position has occurred five times
+    InsufficientMaterial, // Neither side has the pieces to deliver checkmate
+    DeadPosition,         // The pieces are locked so that no sequence of moves can checkmate
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameStatus {
+    Ongoing,
+    Checkmate { winner: Color },
+    Stalemate,
+    Draw { reason: DrawReason },
+}
@@ -0,0 +1,49 @@
+use crate::pieces::Piece;
+
+// Languages whose piece letters we can read and write algebraic notation in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    English,
+    German,
+    French,
+    Spanish,
+    Italian,
+    Dutch,
+    Portuguese,
+}
+
+impl Language {
+    // Letters for king, queen, rook, bishop and knight, in that order
+    pub fn piece_letters(&self) -> [char; 5] {
+        match self {
+            Language::English => ['K', 'Q', 'R', 'B', 'N'],
+            Language::German => ['K', 'D', 'T', 'L', 'S'],
+            Language::French => ['R', 'D', 'T', 'F', 'C'],
+            Language::Spanish => ['R', 'D', 'T', 'A', 'C'],
+            Language::Italian => ['R', 'D', 'T', 'A', 'C'],
+            Language::Dutch => ['K', 'D', 'T', 'L', 'P'],
+            Language::Portuguese => ['R', 'D', 'T', 'B', 'C'],
+        }
+    }
+
+    // The letter for a piece, None for pawns, which have no letter in algebraic notation
+    pub fn piece_letter(&self, piece: Piece) -> Option<char> {
+        let [king, queen, rook, bishop, knight] = self.piece_letters();
+        match piece {
+            Piece::King => Some(king),
+            Piece::Queen => Some(queen),
+            Piece::Rook => Some(rook),
+            Piece::Bishop => Some(bishop),
+            Piece::Knight => Some(knight),
+            Piece::Pawn => None,
+        }
+    }
+
+    pub fn piece_from_letter(&self, letter: char) -> Option<Piece> {
+        let pieces = [Piece::King, Piece::Queen, Piece::Rook, Piece::Bishop, Piece::Knight];
+        self.piece_letters()
+            .iter()
+            .position(|piece_letter| *piece_letter == letter)
+            .map(|index| pieces[index])
+    }
+}
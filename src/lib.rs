@@ -0,0 +1,26 @@
+pub mod attacks;
+pub mod bitboard;
+pub mod board;
+pub mod castling_rights;
+pub mod chess;
+pub mod error;
+pub mod fen;
+pub mod game_status;
+pub mod language;
+pub mod magic;
+pub mod notation;
+pub mod packed_move;
+pub mod perft;
+pub mod perft_cache;
+pub mod r#move;
+pub mod move_list;
+pub mod move_stage;
+pub mod pgn;
+pub mod pieces;
+pub mod san;
+pub mod square;
+pub mod staged_moves;
+pub mod tile;
+pub mod uci;
+pub mod validation;
+pub mod zobrist;
@@ -1,31 +1,30 @@
-mod pieces;
-mod board;
-mod r#move;
-mod chess;
-mod fen;
-mod castling_rights;
-mod tile;
+use cratechess::chess;
 
 fn main() {
     let fen = "4k3/8/5p2/3pP3/8/8/8/4K3 w - f6 0 1";
 
     // Create a new Chess game with a board from the provided FEN string
-    let game = chess::Chess::from_fen(fen);
+    let game = match chess::Chess::from_fen(fen) {
+        Ok(game) => game,
+        Err(err) => {
+            eprintln!("Invalid FEN: {}", err);
+            return;
+        }
+    };
     // let game = chess::Chess::default();
 
     // Print the board to verify the setup
     game.board.pretty_print();
 
+    println!("FEN: {}", game.to_fen());
     println!("Turn: {:?}", game.get_turn());
-    println!("{:?}", game.castling_rights);
+    println!("Castling Rights: {}", game.castling_rights.to_fen_string());
     println!("En Passant Target: {:?}", game.en_passant_target);
     println!("Halfmove Clock: {}", game.halfmove_clock);
     println!("Fullmove Number: {}", game.fullmove_number);
+    println!("Status: {:?}", game.status());
 
-    println!("Legal Moves: [");
-    for legal_move in game.get_legal_moves().iter() {
-        println!("  {{\n    from: {:?}\n    to: {:?}\n    piece: {:?}\n    promotion: {:?}\n  }},", legal_move.from.name.get_notation_name(), legal_move.to.name.get_notation_name(), legal_move.piece, legal_move.promotion);
-    }
-    println!("]")
+    let legal_moves: Vec<String> = game.get_legal_moves().iter().map(|legal_move| game.move_to_san(legal_move)).collect();
+    println!("Legal Moves: {}", legal_moves.join(" "));
 
 }
@@ -1,16 +1,158 @@
-use crate::{pieces::ChessPiece, tile::Tile};
+use crate::{pieces::{ChessPiece, Piece}, square::{File, Square}, tile::Tile};
 
 
+// What a move does beyond taking a piece from one tile to another. The discriminants are the
+// 4-bit flags used by PackedMove: bit 2 marks captures and bit 3 marks promotions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MoveKind {
+    Quiet = 0,
+    DoublePawnPush = 1,
+    KingCastle = 2,
+    QueenCastle = 3,
+    Capture = 4,
+    EnPassant = 5,
+    KnightPromotion = 8,
+    BishopPromotion = 9,
+    RookPromotion = 10,
+    QueenPromotion = 11,
+    KnightPromotionCapture = 12,
+    BishopPromotionCapture = 13,
+    RookPromotionCapture = 14,
+    QueenPromotionCapture = 15,
+}
+
+impl MoveKind {
+    pub fn from_bits(bits: u8) -> Option<Self> {
+        let kind = match bits {
+            0 => MoveKind::Quiet,
+            1 => MoveKind::DoublePawnPush,
+            2 => MoveKind::KingCastle,
+            3 => MoveKind::QueenCastle,
+            4 => MoveKind::Capture,
+            5 => MoveKind::EnPassant,
+            8 => MoveKind::KnightPromotion,
+            9 => MoveKind::BishopPromotion,
+            10 => MoveKind::RookPromotion,
+            11 => MoveKind::QueenPromotion,
+            12 => MoveKind::KnightPromotionCapture,
+            13 => MoveKind::BishopPromotionCapture,
+            14 => MoveKind::RookPromotionCapture,
+            15 => MoveKind::QueenPromotionCapture,
+            _ => return None,
+        };
+        Some(kind)
+    }
+
+    pub fn promotion(promotion: Piece, is_capture: bool) -> Option<Self> {
+        let kind = match (promotion, is_capture) {
+            (Piece::Knight, false) => MoveKind::KnightPromotion,
+            (Piece::Bishop, false) => MoveKind::BishopPromotion,
+            (Piece::Rook, false) => MoveKind::RookPromotion,
+            (Piece::Queen, false) => MoveKind::QueenPromotion,
+            (Piece::Knight, true) => MoveKind::KnightPromotionCapture,
+            (Piece::Bishop, true) => MoveKind::BishopPromotionCapture,
+            (Piece::Rook, true) => MoveKind::RookPromotionCapture,
+            (Piece::Queen, true) => MoveKind::QueenPromotionCapture,
+            _ => return None,
+        };
+        Some(kind)
+    }
+
+    pub fn is_capture(&self) -> bool {
+        *self as u8 & 0b0100 != 0
+    }
+
+    pub fn is_promotion(&self) -> bool {
+        *self as u8 & 0b1000 != 0
+    }
+
+    pub fn is_castling(&self) -> bool {
+        matches!(self, MoveKind::KingCastle | MoveKind::QueenCastle)
+    }
+
+    pub fn promotion_piece(&self) -> Option<Piece> {
+        if !self.is_promotion() {
+            return None;
+        }
+        Some(match *self as u8 & 0b0011 {
+            0 => Piece::Knight,
+            1 => Piece::Bishop,
+            2 => Piece::Rook,
+            _ => Piece::Queen,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Move {
     pub from: Tile,
     pub to: Tile,
     pub piece: ChessPiece,
     pub promotion: Option<ChessPiece>,
+    pub castling_rook: Option<Square>, // For castles, the square the rook starts from
 }
 
 impl Move {
     pub fn new(from: Tile, to: Tile, piece: ChessPiece, promotion: Option<ChessPiece>) -> Self {
-        Self { from, to, piece, promotion }
+        Self { from, to, piece, promotion, castling_rook: None }
+    }
+
+    // A castle, with `to` the king's destination on the g or c file and `rook` the square the
+    // rook starts from, which Chess960 allows on any file
+    pub fn new_castling(from: Tile, to: Tile, king: ChessPiece, rook: Square) -> Self {
+        Self { from, to, piece: king, promotion: None, castling_rook: Some(rook) }
+    }
+
+    pub fn is_castling(&self) -> bool {
+        self.castling_rook.is_some()
+    }
+
+    // Long algebraic coordinates as spoken by UCI engines, e.g. "e2e4", "e7e8q" or "e1g1" for
+    // castling, where the king's destination stands for the whole castle
+    pub fn to_uci(&self) -> String {
+        let mut uci = format!("{}{}", self.from.name, self.to.name);
+        if let Some(promotion) = self.promotion {
+            uci.push(promotion.to_fen_char().to_ascii_lowercase());
+        }
+        uci
+    }
+
+    // UCI coordinates in Chess960 mode, which writes castling as the king capturing its own rook
+    pub fn to_uci_chess960(&self) -> String {
+        match self.castling_rook {
+            Some(rook) => format!("{}{}", self.from.name, rook),
+            None => self.to_uci(),
+        }
+    }
+
+    // The rook's origin and destination squares when the move is a castle. The rook ends on the
+    // f file next to the king on g, or on the d file next to the king on c.
+    pub fn castling_rook_squares(&self) -> Option<(Square, Square)> {
+        let rook_from = self.castling_rook?;
+        let file = if rook_from.file() > self.from.name.file() { File::F } else { File::D };
+        Some((rook_from, Square::new(file, rook_from.rank())))
+    }
+
+    // Classify the move from the tiles it was built from: `to` still holds any captured piece,
+    // so a pawn moving diagonally onto an empty tile can only be capturing en passant
+    pub fn kind(&self) -> MoveKind {
+        let is_capture = self.to.is_occupied();
+        let is_pawn = self.piece.piece_type == Piece::Pawn;
+        let (from_x, from_y) = self.from.get_coords();
+        let (to_x, to_y) = self.to.get_coords();
+
+        if let Some(promotion) = self.promotion.and_then(|piece| MoveKind::promotion(piece.piece_type, is_capture)) {
+            promotion
+        } else if let Some(rook) = self.castling_rook {
+            if rook.file() > self.from.name.file() { MoveKind::KingCastle } else { MoveKind::QueenCastle }
+        } else if is_capture {
+            MoveKind::Capture
+        } else if is_pawn && from_x != to_x {
+            MoveKind::EnPassant
+        } else if is_pawn && from_y.abs_diff(to_y) == 2 {
+            MoveKind::DoublePawnPush
+        } else {
+            MoveKind::Quiet
+        }
     }
 }
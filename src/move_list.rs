@@ -0,0 +1,168 @@
+use std::ops::{Deref, DerefMut};
+
+use crate::pieces::{ChessPiece, Color, Piece};
+use crate::r#move::Move;
+use crate::square::Square;
+use crate::tile::Tile;
+
+// No legal chess position has more than 218 moves, so 256 leaves room for pseudo-legal ones.
+// FEN can describe positions with far more, such as a board full of queens, which is why the
+// Vec-returning move getters do not go through a MoveList.
+pub const MAX_MOVES: usize = 256;
+
+// Fills the unused slots so the backing array needs no heap or unsafe initialization
+const EMPTY_SLOT: Move = Move {
+    from: Tile { piece: None, name: Square::A1 },
+    to: Tile { piece: None, name: Square::A1 },
+    piece: ChessPiece { piece_type: Piece::Pawn, color: Color::White },
+    promotion: None,
+    castling_rook: None,
+};
+
+// Where move generation puts the moves it finds: a MoveList in hot paths, or a Vec when every
+// move must be kept however many there are
+pub trait MoveSink {
+    fn push(&mut self, mv: Move);
+    fn len(&self) -> usize;
+    fn retain(&mut self, keep: impl FnMut(&Move) -> bool);
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl MoveSink for MoveList {
+    fn push(&mut self, mv: Move) {
+        MoveList::push(self, mv)
+    }
+
+    fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    fn retain(&mut self, keep: impl FnMut(&Move) -> bool) {
+        MoveList::retain(self, keep)
+    }
+}
+
+impl MoveSink for Vec<Move> {
+    fn push(&mut self, mv: Move) {
+        Vec::push(self, mv)
+    }
+
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn retain(&mut self, keep: impl FnMut(&Move) -> bool) {
+        Vec::retain(self, keep)
+    }
+}
+
+// A fixed-capacity list of moves that lives on the stack, for move generation in hot paths
+#[derive(Debug, Clone, Copy)]
+pub struct MoveList {
+    moves: [Move; MAX_MOVES],
+    len: usize,
+}
+
+impl MoveList {
+    pub fn new() -> Self {
+        MoveList { moves: [EMPTY_SLOT; MAX_MOVES], len: 0 }
+    }
+
+    // Drops the move if the list is already full, which only positions that could not arise in
+    // a game can cause
+    pub fn push(&mut self, mv: Move) {
+        if let Some(slot) = self.moves.get_mut(self.len) {
+            *slot = mv;
+            self.len += 1;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    // Keep only the moves the predicate accepts, preserving their order
+    pub fn retain(&mut self, mut keep: impl FnMut(&Move) -> bool) {
+        let mut kept = 0;
+        for index in 0..self.len {
+            let mv = self.moves[index];
+            if keep(&mv) {
+                self.moves[kept] = mv;
+                kept += 1;
+            }
+        }
+        self.len = kept;
+    }
+
+    pub fn as_slice(&self) -> &[Move] {
+        &self.moves[..self.len]
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [Move] {
+        &mut self.moves[..self.len]
+    }
+}
+
+impl Default for MoveList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for MoveList {
+    type Target = [Move];
+
+    fn deref(&self) -> &[Move] {
+        self.as_slice()
+    }
+}
+
+impl DerefMut for MoveList {
+    fn deref_mut(&mut self) -> &mut [Move] {
+        self.as_mut_slice()
+    }
+}
+
+impl<'a> IntoIterator for &'a MoveList {
+    type Item = &'a Move;
+    type IntoIter = std::slice::Iter<'a, Move>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_slice().iter()
+    }
+}
+
+// Owning iterator over a MoveList, yielding the moves in the order they were pushed
+pub struct IntoIter {
+    list: MoveList,
+    index: usize,
+}
+
+impl Iterator for IntoIter {
+    type Item = Move;
+
+    fn next(&mut self) -> Option<Move> {
+        let mv = self.list.as_slice().get(self.index).copied()?;
+        self.index += 1;
+        Some(mv)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.list.len - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for IntoIter {}
+
+impl IntoIterator for MoveList {
+    type Item = Move;
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> IntoIter {
+        IntoIter { list: self, index: 0 }
+    }
+}
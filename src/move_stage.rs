@@ -0,0 +1,18 @@
+// Which part of the move list a generator should produce. Search code generates captures
+// first, since they are the moves most likely to cut the search short, and quiets only after.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveStage {
+    All,
+    Captures, // Including en passant and promotions that capture
+    Quiets,   // Pushes, non-capturing promotions, castling and other moves to empty squares
+}
+
+impl MoveStage {
+    pub fn includes_captures(&self) -> bool {
+        matches!(self, MoveStage::All | MoveStage::Captures)
+    }
+
+    pub fn includes_quiets(&self) -> bool {
+        matches!(self, MoveStage::All | MoveStage::Quiets)
+    }
+}
@@ -0,0 +1,345 @@
+use crate::board::Board;
+use crate::chess::Chess;
+use crate::error::ChessError;
+use crate::language::Language;
+use crate::pieces::{Color, Piece};
+use crate::r#move::{Move, MoveKind};
+use crate::san;
+use crate::square::{File, Rank, Square};
+
+// The ways a move can be written besides plain SAN
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Notation {
+    San,                  // "Nf3", "exd5", "e8=Q+"
+    Figurine,             // SAN with piece glyphs: "♘f3"
+    Localized(Language),  // SAN with the language's piece letters: "Sf3" in German
+    LongAlgebraic,        // Origin and destination: "Ng1-f3", "e4xd5"
+    Descriptive,          // English descriptive: "P-K4", "NxP", "NxQB3"
+    Iccf,                 // ICCF numeric: "7163"
+}
+
+// Write a legal move of the position in the given notation
+pub fn write_move(chess: &Chess, mv: &Move, notation: Notation) -> String {
+    match notation {
+        Notation::San => san::move_to_san(chess, mv),
+        Notation::Figurine => {
+            san::write_san(chess, mv, |piece| (piece.piece_type != Piece::Pawn).then(|| Board::piece_to_unicode(&piece)))
+        }
+        Notation::Localized(language) => san::write_san(chess, mv, |piece| language.piece_letter(piece.piece_type)),
+        Notation::LongAlgebraic => long_algebraic(chess, mv),
+        Notation::Descriptive => descriptive(chess, mv),
+        Notation::Iccf => iccf(mv),
+    }
+}
+
+// SAN written with the language's piece letters, or with figurines of either color, in all the
+// sloppy forms parse_san accepts
+pub fn parse_localized_san(chess: &Chess, text: &str, language: Language) -> Result<Move, ChessError> {
+    // Pawns have no letter in SAN, so a pawn figurine is simply dropped
+    let letters: String = text
+        .chars()
+        .filter_map(|c| match figurine_piece(c) {
+            Some(Piece::Pawn) => None,
+            Some(piece) => language.piece_letter(piece),
+            None => Some(c),
+        })
+        .collect();
+    san::parse_san_with(chess, &letters, |letter| language.piece_from_letter(letter)).map_err(|error| match error {
+        ChessError::InvalidMoveNotation(_) => ChessError::InvalidMoveNotation(text.to_string()),
+        ChessError::IllegalMove(_) => ChessError::IllegalMove(text.to_string()),
+        ChessError::AmbiguousMove(_) => ChessError::AmbiguousMove(text.to_string()),
+        error => error,
+    })
+}
+
+// ICCF numeric notation: origin and destination as file and rank digits, plus a digit for the
+// promotion piece (1 queen, 2 rook, 3 bishop, 4 knight)
+pub fn parse_iccf(chess: &Chess, text: &str) -> Result<Move, ChessError> {
+    let invalid = || ChessError::InvalidMoveNotation(text.to_string());
+    let digits: Vec<usize> = text.trim().chars().map(|c| c.to_digit(10).map(|digit| digit as usize)).collect::<Option<_>>().ok_or_else(invalid)?;
+
+    let square = |file: usize, rank: usize| {
+        Some(Square::new(File::from_index(file.checked_sub(1)?)?, Rank::from_index(rank.checked_sub(1)?)?))
+    };
+    let (from, to, promotion) = match digits.as_slice() {
+        [from_file, from_rank, to_file, to_rank, promotion @ ..] if promotion.len() <= 1 => {
+            let promotion = match promotion {
+                [] => None,
+                [1] => Some(Piece::Queen),
+                [2] => Some(Piece::Rook),
+                [3] => Some(Piece::Bishop),
+                [4] => Some(Piece::Knight),
+                _ => return Err(invalid()),
+            };
+            (square(*from_file, *from_rank).ok_or_else(invalid)?, square(*to_file, *to_rank).ok_or_else(invalid)?, promotion)
+        }
+        _ => return Err(invalid()),
+    };
+
+    chess
+        .get_legal_moves()
+        .into_iter()
+        .find(|mv| mv.from.name == from && mv.to.name == to && mv.promotion.map(|piece| piece.piece_type) == promotion)
+        .ok_or_else(|| ChessError::IllegalMove(text.to_string()))
+}
+
+fn figurine_piece(glyph: char) -> Option<Piece> {
+    match glyph {
+        '♔' | '♚' => Some(Piece::King),
+        '♕' | '♛' => Some(Piece::Queen),
+        '♖' | '♜' => Some(Piece::Rook),
+        '♗' | '♝' => Some(Piece::Bishop),
+        '♘' | '♞' => Some(Piece::Knight),
+        '♙' | '♟' => Some(Piece::Pawn),
+        _ => None,
+    }
+}
+
+fn long_algebraic(chess: &Chess, mv: &Move) -> String {
+    let mut text = String::new();
+    if mv.is_castling() {
+        text.push_str(if mv.kind() == MoveKind::KingCastle { "O-O" } else { "O-O-O" });
+    } else {
+        if let Some(letter) = san::piece_letter(mv.piece.piece_type) {
+            text.push(letter);
+        }
+        text.push_str(&mv.from.name.to_string());
+        text.push(if mv.kind().is_capture() { 'x' } else { '-' });
+        text.push_str(&mv.to.name.to_string());
+        if let Some(letter) = mv.promotion.and_then(|piece| san::piece_letter(piece.piece_type)) {
+            text.push('=');
+            text.push(letter);
+        }
+    }
+    text.push_str(san::check_suffix(chess, mv));
+    text
+}
+
+// How much detail a descriptive move needs before no other legal move reads the same
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Detail {
+    Captured,    // "NxP": name the captured piece
+    Destination, // "NxQB3": name the square instead
+    Origin,      // "N(K5)xQB3": and the square the piece comes from
+}
+
+fn descriptive(chess: &Chess, mv: &Move) -> String {
+    let mut text = if mv.is_castling() {
+        String::from(if mv.kind() == MoveKind::KingCastle { "O-O" } else { "O-O-O" })
+    } else {
+        let legal_moves = chess.get_legal_moves();
+        let rivals: Vec<&Move> = legal_moves
+            .iter()
+            .filter(|other| other.piece == mv.piece && other.promotion == mv.promotion)
+            .filter(|other| (other.from.name, other.to.name) != (mv.from.name, mv.to.name))
+            .collect();
+
+        [Detail::Captured, Detail::Destination, Detail::Origin]
+            .into_iter()
+            .map(|detail| (detail, descriptive_body(mv, detail)))
+            .find(|(detail, body)| *detail == Detail::Origin || rivals.iter().all(|rival| descriptive_body(rival, *detail) != *body))
+            .map(|(_, body)| body)
+            .unwrap_or_default()
+    };
+
+    text.push_str(match san::check_suffix(chess, mv) {
+        "#" => " mate",
+        "+" => " ch",
+        _ => "",
+    });
+    text
+}
+
+fn descriptive_body(mv: &Move, detail: Detail) -> String {
+    let color = mv.piece.color;
+    let mut text = descriptive_letter(mv.piece.piece_type).to_string();
+    if detail == Detail::Origin {
+        text.push_str(&format!("({})", descriptive_square(mv.from.name, color)));
+    }
+
+    let is_en_passant = mv.kind() == MoveKind::EnPassant;
+    if mv.kind().is_capture() {
+        text.push('x');
+        match (detail, mv.to.piece) {
+            (Detail::Captured, Some(captured)) => text.push(descriptive_letter(captured.piece_type)),
+            (Detail::Captured, None) => text.push('P'), // En passant
+            _ => text.push_str(&descriptive_square(mv.to.name, color)),
+        }
+    } else {
+        text.push('-');
+        text.push_str(&descriptive_square(mv.to.name, color));
+    }
+
+    if let Some(promotion) = mv.promotion {
+        text.push('=');
+        text.push(descriptive_letter(promotion.piece_type));
+    }
+    if is_en_passant {
+        text.push_str(" e.p.");
+    }
+    text
+}
+
+fn descriptive_letter(piece: Piece) -> char {
+    san::piece_letter(piece).unwrap_or('P')
+}
+
+// Files are named after the pieces that start on them and ranks are counted from the mover's
+// own side of the board, so White's e4 is Black's e5: both are "K4"
+fn descriptive_square(square: Square, color: Color) -> String {
+    let file = ["QR", "QN", "QB", "Q", "K", "KB", "KN", "KR"][square.file().index()];
+    let rank = match color {
+        Color::White => square.rank().index() + 1,
+        Color::Black => 8 - square.rank().index(),
+    };
+    format!("{}{}", file, rank)
+}
+
+fn iccf(mv: &Move) -> String {
+    let digits = |square: Square| format!("{}{}", square.file().index() + 1, square.rank().index() + 1);
+    let mut text = format!("{}{}", digits(mv.from.name), digits(mv.to.name));
+    if let Some(promotion) = mv.promotion {
+        text.push(match promotion.piece_type {
+            Piece::Queen => '1',
+            Piece::Rook => '2',
+            Piece::Bishop => '3',
+            _ => '4',
+        });
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    const PROMOTION: &str = "1n2k3/P7/8/8/8/8/8/4K3 w - - 0 1";
+    const EN_PASSANT: &str = "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1";
+    const CASTLING: &str = "4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1";
+
+    fn written(fen: &str, uci: &str, notation: Notation) -> String {
+        let chess = Chess::from_fen(fen).unwrap();
+        write_move(&chess, &chess.parse_uci(uci).unwrap(), notation)
+    }
+
+    fn localized(fen: &str, text: &str, language: Language) -> Result<String, ChessError> {
+        let chess = Chess::from_fen(fen).unwrap();
+        parse_localized_san(&chess, text, language).map(|mv| chess.move_to_san(&mv))
+    }
+
+    fn iccf_parsed(fen: &str, text: &str) -> Result<String, ChessError> {
+        let chess = Chess::from_fen(fen).unwrap();
+        parse_iccf(&chess, text).map(|mv| chess.move_to_san(&mv))
+    }
+
+    #[test]
+    fn san_and_figurine() {
+        assert_eq!(written(START, "g1f3", Notation::San), "Nf3");
+        assert_eq!(written(START, "g1f3", Notation::Figurine), "♘f3");
+        assert_eq!(written(START, "e2e4", Notation::Figurine), "e4");
+        assert_eq!(written(PROMOTION, "a7b8q", Notation::Figurine), "axb8=♕+");
+    }
+
+    #[test]
+    fn localized_letters() {
+        assert_eq!(written(START, "g1f3", Notation::Localized(Language::German)), "Sf3");
+        assert_eq!(written(START, "g1f3", Notation::Localized(Language::French)), "Cf3");
+        assert_eq!(written(PROMOTION, "a7a8q", Notation::Localized(Language::German)), "a8=D");
+        assert_eq!(written(CASTLING, "e1c1", Notation::Localized(Language::Spanish)), "O-O-O");
+    }
+
+    #[test]
+    fn long_algebraic() {
+        assert_eq!(written(START, "g1f3", Notation::LongAlgebraic), "Ng1-f3");
+        assert_eq!(written(START, "e2e4", Notation::LongAlgebraic), "e2-e4");
+        assert_eq!(written(EN_PASSANT, "e5d6", Notation::LongAlgebraic), "e5xd6");
+        assert_eq!(written(PROMOTION, "a7a8n", Notation::LongAlgebraic), "a7-a8=N");
+        assert_eq!(written(CASTLING, "e1g1", Notation::LongAlgebraic), "O-O");
+    }
+
+    #[test]
+    fn descriptive_counts_ranks_from_the_mover() {
+        assert_eq!(written(START, "e2e4", Notation::Descriptive), "P-K4");
+        assert_eq!(written("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1", "e7e5", Notation::Descriptive), "P-K4");
+        assert_eq!(written(START, "b1c3", Notation::Descriptive), "N-QB3");
+    }
+
+    #[test]
+    fn descriptive_adds_detail_until_unambiguous() {
+        // Only one capture, so naming the captured piece is enough
+        assert_eq!(written("4k3/8/8/8/8/2p5/8/1N2K3 w - - 0 1", "b1c3", Notation::Descriptive), "NxP");
+        // A pawn on a3 makes NxP ambiguous, but the square is not
+        assert_eq!(written("4k3/8/8/8/8/p1p5/8/1N2K3 w - - 0 1", "b1c3", Notation::Descriptive), "NxQB3");
+        // Two knights take on the same square, so the origin is needed too
+        assert_eq!(written("4k3/8/8/8/N3N3/2p5/8/4K3 w - - 0 1", "e4c3", Notation::Descriptive), "N(K4)xQB3");
+        assert_eq!(written("4k3/8/8/8/N3N3/2p5/8/4K3 w - - 0 1", "a4c3", Notation::Descriptive), "N(QR4)xQB3");
+        // Black counts ranks from its own side
+        assert_eq!(written("4k3/8/8/n3n3/2P5/8/8/4K3 b - - 0 1", "e5c4", Notation::Descriptive), "N(K4)xQB5");
+    }
+
+    #[test]
+    fn descriptive_promotion_en_passant_castling_and_check() {
+        assert_eq!(written(PROMOTION, "a7a8q", Notation::Descriptive), "P-QR8=Q");
+        assert_eq!(written(PROMOTION, "a7b8q", Notation::Descriptive), "PxN=Q ch");
+        assert_eq!(written(PROMOTION, "a7b8r", Notation::Descriptive), "PxN=R ch");
+        assert_eq!(written(EN_PASSANT, "e5d6", Notation::Descriptive), "PxP e.p.");
+        assert_eq!(written(CASTLING, "e1g1", Notation::Descriptive), "O-O");
+        assert_eq!(written(CASTLING, "e1c1", Notation::Descriptive), "O-O-O");
+    }
+
+    #[test]
+    fn iccf_digits() {
+        assert_eq!(written(START, "g1f3", Notation::Iccf), "7163");
+        assert_eq!(written(CASTLING, "e1g1", Notation::Iccf), "5171");
+        for (uci, iccf) in [("a7a8q", "17181"), ("a7a8r", "17182"), ("a7a8b", "17183"), ("a7a8n", "17184")] {
+            assert_eq!(written(PROMOTION, uci, Notation::Iccf), iccf);
+        }
+    }
+
+    #[test]
+    fn localized_san_in_every_language() {
+        for language in [
+            Language::English,
+            Language::German,
+            Language::French,
+            Language::Spanish,
+            Language::Italian,
+            Language::Dutch,
+            Language::Portuguese,
+        ] {
+            let knight = language.piece_letter(Piece::Knight).unwrap();
+            assert_eq!(localized(START, &format!("{}f3", knight), language), Ok(String::from("Nf3")), "{:?}", language);
+            let queen = language.piece_letter(Piece::Queen).unwrap();
+            assert_eq!(localized(PROMOTION, &format!("a8={}", queen), language), Ok(String::from("a8=Q")), "{:?}", language);
+            assert_eq!(localized(START, "e4", language), Ok(String::from("e4")), "{:?}", language);
+        }
+    }
+
+    #[test]
+    fn localized_san_reads_figurines_of_either_color() {
+        assert_eq!(localized(START, "♘f3", Language::German), Ok(String::from("Nf3")));
+        assert_eq!(localized(START, "♞f3", Language::German), Ok(String::from("Nf3")));
+        assert_eq!(localized(START, "♙d4", Language::English), Ok(String::from("d4")));
+        assert_eq!(localized(START, "♟d4", Language::French), Ok(String::from("d4")));
+        assert_eq!(localized(PROMOTION, "a8=♛", Language::English), Ok(String::from("a8=Q")));
+    }
+
+    #[test]
+    fn localized_san_reports_the_original_text() {
+        assert_eq!(localized(START, "Sf4", Language::German), Err(ChessError::IllegalMove(String::from("Sf4"))));
+        assert_eq!(localized(START, "Xf3", Language::German), Err(ChessError::InvalidMoveNotation(String::from("Xf3"))));
+    }
+
+    #[test]
+    fn iccf_parsing() {
+        assert_eq!(iccf_parsed(START, "5254"), Ok(String::from("e4")));
+        assert_eq!(iccf_parsed(START, "7163"), Ok(String::from("Nf3")));
+        assert_eq!(iccf_parsed(PROMOTION, "17181"), Ok(String::from("a8=Q")));
+        assert_eq!(iccf_parsed(PROMOTION, "17284"), Ok(String::from("axb8=N")));
+        assert_eq!(iccf_parsed(START, "5255"), Err(ChessError::IllegalMove(String::from("5255"))));
+        for text in ["525", "9254", "0254", "171815", "17185", "e2e4"] {
+            assert_eq!(iccf_parsed(PROMOTION, text), Err(ChessError::InvalidMoveNotation(String::from(text))), "{}", text);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use crate::board::Board;
+use crate::pieces::ChessPiece;
+use crate::r#move::{Move, MoveKind};
+use crate::square::{File, Square};
+
+// A move packed into 16 bits for transposition tables and game databases:
+// bits 0-5 hold the origin square, bits 6-11 the destination and bits 12-15 the MoveKind.
+// Castles store the castling rook's square as the destination, which Chess960 needs to tell them
+// apart and from which the king's destination follows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PackedMove(u16);
+
+impl PackedMove {
+    pub fn new(from: Square, to: Square, kind: MoveKind) -> Self {
+        PackedMove(from.index() as u16 | (to.index() as u16) << 6 | (kind as u16) << 12)
+    }
+
+    // Rebuild a packed move from its raw bits, rejecting the two unused kind values
+    pub fn from_bits(bits: u16) -> Option<Self> {
+        MoveKind::from_bits((bits >> 12) as u8).map(|_| PackedMove(bits))
+    }
+
+    pub fn to_bits(&self) -> u16 {
+        self.0
+    }
+
+    pub fn from_square(&self) -> Square {
+        Square::ALL[(self.0 & 0x3f) as usize]
+    }
+
+    pub fn to_square(&self) -> Square {
+        Square::ALL[(self.0 >> 6 & 0x3f) as usize]
+    }
+
+    pub fn kind(&self) -> MoveKind {
+        MoveKind::from_bits((self.0 >> 12) as u8).expect("kind bits are validated on construction")
+    }
+
+    // Expand back into a full Move by reading the moving and captured pieces off the board the
+    // move is to be played on. Returns None if there is no piece on the origin square.
+    pub fn to_move(&self, board: &Board) -> Option<Move> {
+        let from = board.tile(self.from_square());
+        let to = board.tile(self.to_square());
+        let piece = from.piece?;
+        let kind = self.kind();
+
+        if kind.is_castling() {
+            let king_to = if kind == MoveKind::KingCastle { File::G } else { File::C };
+            let king_to = board.tile(Square::new(king_to, from.name.rank()));
+            return Some(Move::new_castling(from, king_to, piece, to.name));
+        }
+        let promotion = kind.promotion_piece().map(|promotion| ChessPiece::new(promotion, piece.color));
+        Some(Move::new(from, to, piece, promotion))
+    }
+}
+
+impl From<Move> for PackedMove {
+    fn from(mv: Move) -> Self {
+        PackedMove::new(mv.from.name, mv.castling_rook.unwrap_or(mv.to.name), mv.kind())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::Chess;
+
+    fn assert_round_trips(fen: &str) -> usize {
+        let chess = Chess::from_fen(fen).unwrap();
+        let moves = chess.get_legal_moves();
+        for mv in &moves {
+            let unpacked = PackedMove::from(*mv).to_move(&chess.board).unwrap();
+            assert_eq!(
+                (unpacked.from.name, unpacked.to.name, unpacked.piece, unpacked.promotion, unpacked.castling_rook, unpacked.kind()),
+                (mv.from.name, mv.to.name, mv.piece, mv.promotion, mv.castling_rook, mv.kind()),
+                "{} in {}",
+                mv.to_uci(),
+                fen
+            );
+        }
+        moves.iter().filter(|mv| mv.is_castling()).count()
+    }
+
+    #[test]
+    fn kiwipete_moves_round_trip() {
+        let castles = assert_round_trips("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+        assert_eq!(castles, 2);
+    }
+
+    #[test]
+    fn promotions_and_en_passant_round_trip() {
+        assert_round_trips("1n2k3/P7/8/3pP3/8/8/8/4K3 w - d6 0 1");
+    }
+
+    #[test]
+    fn chess960_moves_round_trip() {
+        // The king on b1 castles with the rook on a1 or the one on e1
+        let castles = assert_round_trips("rk2r3/pppppppp/8/8/8/8/PPPPPPPP/RK2R3 w AEae - 0 1");
+        assert_eq!(castles, 2);
+    }
+
+    #[test]
+    fn unused_kinds_are_rejected() {
+        for kind in [6u16, 7] {
+            assert_eq!(PackedMove::from_bits(kind << 12), None);
+        }
+        assert!(PackedMove::from_bits(5 << 12).is_some());
+        assert!(PackedMove::from_bits(8 << 12).is_some());
+    }
+}
@@ -0,0 +1,194 @@
+use std::ops::{Add, AddAssign};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use crate::bitboard::Bitboard;
+use crate::chess::Chess;
+use crate::move_list::MoveList;
+use crate::move_stage::MoveStage;
+use crate::perft_cache::PerftCache;
+use crate::r#move::{Move, MoveKind};
+
+// Leaf counts of a perft run, broken down the usual way: every category counts the moves played
+// on the last ply that are of that kind or give that kind of check
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PerftStats {
+    pub nodes: u64,
+    pub captures: u64, // Including en passant
+    pub en_passants: u64,
+    pub castles: u64,
+    pub promotions: u64,
+    pub checks: u64,
+    pub discovered_checks: u64, // Single checks given by a piece other than the one that moved
+    pub double_checks: u64,
+    pub checkmates: u64,
+}
+
+impl Add for PerftStats {
+    type Output = PerftStats;
+
+    fn add(self, other: PerftStats) -> PerftStats {
+        PerftStats {
+            nodes: self.nodes + other.nodes,
+            captures: self.captures + other.captures,
+            en_passants: self.en_passants + other.en_passants,
+            castles: self.castles + other.castles,
+            promotions: self.promotions + other.promotions,
+            checks: self.checks + other.checks,
+            discovered_checks: self.discovered_checks + other.discovered_checks,
+            double_checks: self.double_checks + other.double_checks,
+            checkmates: self.checkmates + other.checkmates,
+        }
+    }
+}
+
+impl AddAssign for PerftStats {
+    fn add_assign(&mut self, other: PerftStats) {
+        *self = *self + other;
+    }
+}
+
+// Walk the legal move tree to the given depth, making and unmaking moves on the position
+pub fn perft(chess: &mut Chess, depth: u32) -> PerftStats {
+    perft_cached(chess, depth, None)
+}
+
+// Perft that looks subtrees up in the cache before walking them, and stores what it walks
+fn perft_cached(chess: &mut Chess, depth: u32, cache: Option<&PerftCache>) -> PerftStats {
+    if depth == 0 {
+        return PerftStats { nodes: 1, ..PerftStats::default() };
+    }
+
+    if let Some(stats) = cache.and_then(|cache| cache.get(chess.hash(), depth)) {
+        return stats;
+    }
+
+    let mut moves = MoveList::new();
+    chess.generate_legal_moves(MoveStage::All, &mut moves);
+
+    let mut stats = PerftStats::default();
+    for mv in moves {
+        stats += subtree(chess, mv, depth, cache);
+    }
+
+    if let Some(cache) = cache {
+        cache.insert(chess.hash(), depth, stats);
+    }
+    stats
+}
+
+// The perft of the position after playing the move, one ply shallower
+fn subtree(chess: &mut Chess, mv: Move, depth: u32, cache: Option<&PerftCache>) -> PerftStats {
+    let undo = chess.make_move(mv);
+    let stats = if depth == 1 { leaf_stats(chess, &mv) } else { perft_cached(chess, depth - 1, cache) };
+    chess.unmake_move(undo);
+    stats
+}
+
+// Perft split by root move, in move generation order
+pub fn perft_divide(chess: &mut Chess, depth: u32) -> Vec<(Move, PerftStats)> {
+    if depth == 0 {
+        return Vec::new();
+    }
+
+    let mut moves = MoveList::new();
+    chess.generate_legal_moves(MoveStage::All, &mut moves);
+
+    moves.into_iter().map(|mv| (mv, subtree(chess, mv, depth, None))).collect()
+}
+
+// Perft with the root moves shared out between threads. Each thread takes the next unclaimed
+// root move until none are left, so a few large subtrees do not leave the other threads idle.
+// A thread count of 0 uses every available core.
+pub fn perft_parallel(chess: &Chess, depth: u32, threads: usize, cache: Option<&PerftCache>) -> PerftStats {
+    if depth == 0 {
+        return PerftStats { nodes: 1, ..PerftStats::default() };
+    }
+
+    perft_divide_parallel(chess, depth, threads, cache)
+        .into_iter()
+        .fold(PerftStats::default(), |total, (_, stats)| total + stats)
+}
+
+// Parallel perft split by root move, in the same order as perft_divide
+pub fn perft_divide_parallel(chess: &Chess, depth: u32, threads: usize, cache: Option<&PerftCache>) -> Vec<(Move, PerftStats)> {
+    if depth == 0 {
+        return Vec::new();
+    }
+
+    let mut moves = MoveList::new();
+    chess.generate_legal_moves(MoveStage::All, &mut moves);
+
+    let threads = match threads {
+        0 => thread::available_parallelism().map_or(1, |threads| threads.get()),
+        threads => threads,
+    }
+    .min(moves.len())
+    .max(1);
+
+    let next_move = AtomicUsize::new(0);
+    let mut results: Vec<(usize, PerftStats)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut chess = chess.clone();
+                    let mut results = Vec::new();
+                    loop {
+                        let index = next_move.fetch_add(1, Ordering::Relaxed);
+                        let Some(&mv) = moves.get(index) else {
+                            break;
+                        };
+                        results.push((index, subtree(&mut chess, mv, depth, cache)));
+                    }
+                    results
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("perft worker thread panicked"))
+            .collect()
+    });
+
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(index, stats)| (moves[index], stats)).collect()
+}
+
+// Classify the move that was just played to reach this leaf
+fn leaf_stats(chess: &Chess, mv: &Move) -> PerftStats {
+    let kind = mv.kind();
+    let mut stats = PerftStats {
+        nodes: 1,
+        captures: kind.is_capture() as u64,
+        en_passants: (kind == MoveKind::EnPassant) as u64,
+        castles: kind.is_castling() as u64,
+        promotions: kind.is_promotion() as u64,
+        ..PerftStats::default()
+    };
+
+    let Some(king_tile) = chess.board.get_king_tile(chess.turn) else {
+        return stats;
+    };
+    let checkers = chess.board.attackers(king_tile.name, chess.turn.opposite());
+    if checkers.is_empty() {
+        return stats;
+    }
+
+    // The moved piece ends up on the destination, and a castling rook beside the king
+    let mut moved = Bitboard::from_square(mv.to.name);
+    if let Some((_, rook_to)) = mv.castling_rook_squares() {
+        moved.insert(rook_to);
+    }
+
+    // A double check is only counted as a double check, not also as a discovered one
+    stats.checks = 1;
+    stats.double_checks = (checkers.count() > 1) as u64;
+    stats.discovered_checks = (stats.double_checks == 0 && (checkers & moved).is_empty()) as u64;
+
+    let mut replies = MoveList::new();
+    chess.generate_legal_moves(MoveStage::All, &mut replies);
+    stats.checkmates = replies.is_empty() as u64;
+
+    stats
+}
@@ -0,0 +1,57 @@
+use std::sync::Mutex;
+
+use crate::perft::PerftStats;
+
+const SHARDS: usize = 64;
+
+#[derive(Debug, Clone, Copy)]
+struct CacheEntry {
+    hash: u64,
+    depth: u32,
+    stats: PerftStats,
+}
+
+// A fixed-size table of perft subtree results keyed by position hash and remaining depth. It is
+// split into separately locked shards so threads rarely wait on each other, and a new result
+// always replaces whatever was stored in its slot.
+#[derive(Debug)]
+pub struct PerftCache {
+    shards: Vec<Mutex<Vec<Option<CacheEntry>>>>,
+    slots_per_shard: usize,
+}
+
+impl PerftCache {
+    // A cache holding up to `entries` results, rounded up to a multiple of the shard count
+    pub fn new(entries: usize) -> Self {
+        let slots_per_shard = entries.div_ceil(SHARDS).max(1);
+        let shards = (0..SHARDS).map(|_| Mutex::new(vec![None; slots_per_shard])).collect();
+        PerftCache { shards, slots_per_shard }
+    }
+
+    pub fn get(&self, hash: u64, depth: u32) -> Option<PerftStats> {
+        let (shard, slot) = self.locate(hash);
+        let shard = self.shards[shard].lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        shard[slot]
+            .filter(|entry| entry.hash == hash && entry.depth == depth)
+            .map(|entry| entry.stats)
+    }
+
+    pub fn insert(&self, hash: u64, depth: u32, stats: PerftStats) {
+        let (shard, slot) = self.locate(hash);
+        let mut shard = self.shards[shard].lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        shard[slot] = Some(CacheEntry { hash, depth, stats });
+    }
+
+    pub fn clear(&self) {
+        for shard in &self.shards {
+            shard.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).fill(None);
+        }
+    }
+
+    // The low bits pick the shard and the rest the slot within it
+    fn locate(&self, hash: u64) -> (usize, usize) {
+        let shard = hash as usize % SHARDS;
+        let slot = (hash / SHARDS as u64 % self.slots_per_shard as u64) as usize;
+        (shard, slot)
+    }
+}
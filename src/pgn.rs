@@ -0,0 +1,416 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::chess::Chess;
+use crate::error::ChessError;
+use crate::r#move::Move;
+
+// The tags every PGN game is expected to carry, in their required order
+pub const SEVEN_TAG_ROSTER: [&str; 7] = ["Event", "Site", "Date", "Round", "White", "Black", "Result"];
+
+// How the movetext says the game ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PgnResult {
+    WhiteWins,  // "1-0"
+    BlackWins,  // "0-1"
+    Draw,       // "1/2-1/2"
+    Unfinished, // "*", or no result at all
+}
+
+impl PgnResult {
+    fn from_symbol(symbol: &str) -> Option<Self> {
+        match symbol {
+            "1-0" => Some(PgnResult::WhiteWins),
+            "0-1" => Some(PgnResult::BlackWins),
+            "1/2-1/2" => Some(PgnResult::Draw),
+            "*" => Some(PgnResult::Unfinished),
+            _ => None,
+        }
+    }
+}
+
+// A mainline move as it appeared in the movetext, with the annotations that followed it
+#[derive(Debug, Clone)]
+pub struct PgnMove {
+    pub mv: Move,
+    pub san: String,          // The move exactly as written
+    pub nags: Vec<u8>,        // Numeric annotation glyphs, with "!" read as $1, "?" as $2 and so on
+    pub comments: Vec<String>,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct PgnGame {
+    pub tags: Vec<(String, String)>, // In the order they appear
+    pub start: Chess,                // The standard start, or the position of the FEN tag
+    pub moves: Vec<PgnMove>,
+    pub comments: Vec<String>,       // Comments before the first move
+    pub result: PgnResult,
+    pub position: Chess,             // The position after the last move
+}
+
+impl PgnGame {
+    // The value of the first tag with the given name
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find(|(tag, _)| tag == name).map(|(_, value)| value.as_str())
+    }
+
+    // Seven Tag Roster names with no tag in this game
+    pub fn missing_roster_tags(&self) -> Vec<&'static str> {
+        SEVEN_TAG_ROSTER.into_iter().filter(|name| self.tag(name).is_none()).collect()
+    }
+}
+
+// Read every game in a PGN file, replaying the mainline of each through Chess. Variations are
+// skipped. The first move that does not parse or is illegal stops the read with its position.
+pub fn read_pgn(text: &str) -> Result<Vec<PgnGame>, ChessError> {
+    let tokens = tokenize(text)?;
+    let mut tokens = tokens.into_iter().peekable();
+    let mut games = Vec::new();
+    while tokens.peek().is_some() {
+        let game = read_game(&mut tokens)?;
+        // Comments trailing the last result are not a game of their own
+        if !game.tags.is_empty() || !game.moves.is_empty() {
+            games.push(game);
+        }
+    }
+    Ok(games)
+}
+
+// Read the first game of a PGN text
+pub fn read_first_game(text: &str) -> Result<PgnGame, ChessError> {
+    read_pgn(text)?
+        .into_iter()
+        .next()
+        .ok_or(ChessError::InvalidPgn { line: 1, column: 1, reason: String::from("no game found") })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TokenKind {
+    TagOpen,
+    TagClose,
+    Text(String), // A quoted tag value
+    Symbol(String),
+    Period,
+    Nag(u8),
+    Comment(String),
+    VariationOpen,
+    VariationClose,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    line: usize,
+    column: usize,
+}
+
+impl Token {
+    fn error(&self, reason: &str) -> ChessError {
+        ChessError::InvalidPgn { line: self.line, column: self.column, reason: reason.to_string() }
+    }
+}
+
+fn read_game(tokens: &mut Peekable<std::vec::IntoIter<Token>>) -> Result<PgnGame, ChessError> {
+    let mut tags = Vec::new();
+    let mut fen_tag = None;
+    while let Some(open) = tokens.next_if(|token| token.kind == TokenKind::TagOpen) {
+        let (Some(name), Some(value), Some(close)) = (tokens.next(), tokens.next(), tokens.next()) else {
+            return Err(open.error("unterminated tag"));
+        };
+        match (name.kind, value.kind, close.kind) {
+            (TokenKind::Symbol(name), TokenKind::Text(value), TokenKind::TagClose) => {
+                if name == "FEN" {
+                    fen_tag = Some((value.clone(), open.clone()));
+                }
+                tags.push((name, value));
+            }
+            _ => return Err(open.error("expected a tag like [Name \"value\"]")),
+        }
+    }
+
+    // A FEN tag sets up the starting position, whether or not SetUp "1" came with it
+    let start = match fen_tag {
+        Some((fen, tag)) => Chess::from_fen(&fen).map_err(|error| tag.error(&format!("invalid FEN tag: {}", error)))?,
+        None => Chess::default(),
+    };
+
+    let mut position = start.clone();
+    let mut moves: Vec<PgnMove> = Vec::new();
+    let mut comments = Vec::new();
+    let mut result = None;
+
+    while let Some(token) = tokens.next_if(|token| token.kind != TokenKind::TagOpen) {
+        match token.kind {
+            TokenKind::Symbol(ref symbol) => {
+                if let Some(symbol_result) = PgnResult::from_symbol(symbol) {
+                    result = Some(symbol_result);
+                    break;
+                }
+                // Move numbers carry nothing the move order does not
+                if symbol.chars().all(|c| c.is_ascii_digit()) {
+                    continue;
+                }
+                let mv = position.parse_san(symbol).map_err(|error| ChessError::InvalidPgnMove {
+                    line: token.line,
+                    column: token.column,
+                    text: symbol.clone(),
+                    error: Box::new(error),
+                })?;
+                position.make_move(mv);
+                moves.push(PgnMove { mv, san: symbol.clone(), nags: Vec::new(), comments: Vec::new(), line: token.line, column: token.column });
+            }
+            TokenKind::Nag(nag) => match moves.last_mut() {
+                Some(last) => last.nags.push(nag),
+                None => return Err(token.error("annotation before the first move")),
+            },
+            TokenKind::Comment(comment) => match moves.last_mut() {
+                Some(last) => last.comments.push(comment),
+                None => comments.push(comment),
+            },
+            TokenKind::VariationOpen => skip_variation(tokens, &token)?,
+            TokenKind::Period => {}
+            TokenKind::VariationClose => return Err(token.error("')' without a variation to close")),
+            TokenKind::TagClose | TokenKind::Text(_) => return Err(token.error("unexpected token in movetext")),
+            TokenKind::TagOpen => unreachable!("tags end the movetext"),
+        }
+    }
+
+    // Without a result in the movetext, fall back on the Result tag
+    let result = result
+        .or_else(|| tags.iter().find(|(name, _)| name == "Result").and_then(|(_, value)| PgnResult::from_symbol(value)))
+        .unwrap_or(PgnResult::Unfinished);
+
+    Ok(PgnGame { tags, start, moves, comments, result, position })
+}
+
+// Skip the rest of a variation, including any variations nested inside it
+fn skip_variation(tokens: &mut Peekable<std::vec::IntoIter<Token>>, open: &Token) -> Result<(), ChessError> {
+    let mut depth = 1;
+    for token in tokens.by_ref() {
+        match token.kind {
+            TokenKind::VariationOpen => depth += 1,
+            TokenKind::VariationClose => depth -= 1,
+            _ => {}
+        }
+        if depth == 0 {
+            return Ok(());
+        }
+    }
+    Err(open.error("unterminated variation"))
+}
+
+// Tracks the line and column of the next character, both counted from 1
+struct Cursor<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl Cursor<'_> {
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn next_if(&mut self, accept: impl Fn(char) -> bool) -> Option<char> {
+        match self.chars.peek() {
+            Some(&c) if accept(c) => self.next(),
+            _ => None,
+        }
+    }
+
+    fn starts_with(&self, text: &str) -> bool {
+        self.chars.clone().take(text.chars().count()).eq(text.chars())
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, ChessError> {
+    let mut cursor = Cursor { chars: text.chars().peekable(), line: 1, column: 1 };
+    let mut tokens = Vec::new();
+
+    loop {
+        let (line, column) = (cursor.line, cursor.column);
+        let error = |reason: &str| ChessError::InvalidPgn { line, column, reason: reason.to_string() };
+
+        // Lines starting with % are escaped for other programs to use
+        if column == 1 && cursor.starts_with("%") {
+            while cursor.next_if(|c| c != '\n').is_some() {}
+            continue;
+        }
+        // En passant suffixes add nothing, the move is the same without them
+        if cursor.starts_with("e.p.") {
+            for _ in 0..4 {
+                cursor.next();
+            }
+            continue;
+        }
+
+        let Some(c) = cursor.next() else {
+            break;
+        };
+        let kind = match c {
+            c if c.is_whitespace() => continue,
+            '[' => TokenKind::TagOpen,
+            ']' => TokenKind::TagClose,
+            '(' => TokenKind::VariationOpen,
+            ')' => TokenKind::VariationClose,
+            '.' => TokenKind::Period,
+            '"' => {
+                let mut value = String::new();
+                loop {
+                    match cursor.next() {
+                        Some('"') => break,
+                        Some('\\') => value.extend(cursor.next()),
+                        Some(c) => value.push(c),
+                        None => return Err(error("unterminated tag value")),
+                    }
+                }
+                TokenKind::Text(value)
+            }
+            '{' => {
+                let mut comment = String::new();
+                loop {
+                    match cursor.next() {
+                        Some('}') => break,
+                        Some(c) => comment.push(c),
+                        None => return Err(error("unterminated comment")),
+                    }
+                }
+                TokenKind::Comment(comment.trim().to_string())
+            }
+            ';' => {
+                let mut comment = String::new();
+                while let Some(c) = cursor.next_if(|c| c != '\n') {
+                    comment.push(c);
+                }
+                TokenKind::Comment(comment.trim().to_string())
+            }
+            '$' => {
+                let mut digits = String::new();
+                while let Some(c) = cursor.next_if(|c| c.is_ascii_digit()) {
+                    digits.push(c);
+                }
+                TokenKind::Nag(digits.parse().map_err(|_| error("expected a number after '$'"))?)
+            }
+            '!' | '?' => {
+                let mut suffix = String::from(c);
+                while let Some(c) = cursor.next_if(|c| c == '!' || c == '?') {
+                    suffix.push(c);
+                }
+                TokenKind::Nag(match suffix.as_str() {
+                    "!" => 1,
+                    "?" => 2,
+                    "!!" => 3,
+                    "??" => 4,
+                    "!?" => 5,
+                    "?!" => 6,
+                    _ => return Err(error("unknown move annotation")),
+                })
+            }
+            '*' => TokenKind::Symbol(String::from("*")),
+            c if c.is_ascii_alphanumeric() => {
+                let mut symbol = String::from(c);
+                while let Some(c) = cursor.next_if(|c| c.is_ascii_alphanumeric() || "_+#=:-/".contains(c)) {
+                    symbol.push(c);
+                }
+                TokenKind::Symbol(symbol)
+            }
+            _ => return Err(error(&format!("unexpected character '{}'", c))),
+        };
+        tokens.push(Token { kind, line, column });
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sans(game: &PgnGame) -> Vec<&str> {
+        game.moves.iter().map(|mv| mv.san.as_str()).collect()
+    }
+
+    #[test]
+    fn tags_in_order_with_escapes() {
+        let game = read_first_game("[Event \"Club \\\"Open\\\"\"]\n[Site \"Here\"]\n[White \"A\"]\n\n1. e4 *").unwrap();
+        assert_eq!(game.tags[0], (String::from("Event"), String::from("Club \"Open\"")));
+        assert_eq!(game.tag("Site"), Some("Here"));
+        assert_eq!(game.tag("Black"), None);
+        assert_eq!(game.missing_roster_tags(), vec!["Date", "Round", "Black", "Result"]);
+    }
+
+    #[test]
+    fn brace_and_rest_of_line_comments() {
+        let game = read_first_game("{Before} 1. e4 {best by test} e5 ; the reply\n2. Nf3 *").unwrap();
+        assert_eq!(game.comments, vec!["Before"]);
+        assert_eq!(game.moves[0].comments, vec!["best by test"]);
+        assert_eq!(game.moves[1].comments, vec!["the reply"]);
+        assert!(game.moves[2].comments.is_empty());
+    }
+
+    #[test]
+    fn numeric_and_suffix_annotations() {
+        let game = read_first_game("1. e4 $1 e5!? 2. Nf3?? $14 Nc6?! *").unwrap();
+        let nags: Vec<&[u8]> = game.moves.iter().map(|mv| mv.nags.as_slice()).collect();
+        assert_eq!(nags, vec![&[1][..], &[5], &[4, 14], &[6]]);
+    }
+
+    #[test]
+    fn variations_are_skipped() {
+        let game = read_first_game("1. e4 (1. d4 d5 (1... Nf6 2. c4)) e5 (1... c5) 2. Nf3 *").unwrap();
+        assert_eq!(sans(&game), vec!["e4", "e5", "Nf3"]);
+    }
+
+    #[test]
+    fn escape_lines_and_en_passant_suffixes_are_skipped() {
+        let game = read_first_game("%engine 1. d4\n1. e4 d5 2. e5 f5 3. exf6 e.p. *").unwrap();
+        assert_eq!(sans(&game), vec!["e4", "d5", "e5", "f5", "exf6"]);
+    }
+
+    #[test]
+    fn fen_tag_sets_up_the_start() {
+        let fen = "4k3/8/8/8/8/8/8/4K2R w K - 0 1";
+        let game = read_first_game(&format!("[SetUp \"1\"]\n[FEN \"{}\"]\n\n1. O-O Kd7 *", fen)).unwrap();
+        assert_eq!(game.start.to_fen(), fen);
+        assert_eq!(game.position.to_fen(), "8/3k4/8/8/8/8/8/5RK1 w - - 2 2");
+    }
+
+    #[test]
+    fn several_games_with_results() {
+        let text = "[Event \"One\"]\n\n1. e4 e5 1-0\n\n[Event \"Two\"]\n\n1. d4 d5 0-1\n\n[Event \"Three\"]\n[Result \"1/2-1/2\"]\n\n1. c4\n";
+        let games = read_pgn(text).unwrap();
+        let summary: Vec<(Option<&str>, PgnResult, usize)> =
+            games.iter().map(|game| (game.tag("Event"), game.result, game.moves.len())).collect();
+        assert_eq!(summary, vec![
+            (Some("One"), PgnResult::WhiteWins, 2),
+            (Some("Two"), PgnResult::BlackWins, 2),
+            (Some("Three"), PgnResult::Draw, 1),
+        ]);
+    }
+
+    #[test]
+    fn illegal_move_reports_its_line_and_column() {
+        let text = "[Event \"Test\"]\n\n1. e4 e5\n2. Nf3 Nc6\n3. Bb5 Nf3 *";
+        assert_eq!(read_pgn(text).unwrap_err(), ChessError::InvalidPgnMove {
+            line: 5,
+            column: 8,
+            text: String::from("Nf3"),
+            error: Box::new(ChessError::IllegalMove(String::from("Nf3"))),
+        });
+    }
+
+    #[test]
+    fn malformed_movetext_reports_its_position() {
+        let error = read_pgn("1. e4\n  e5 {never closed").unwrap_err();
+        assert!(matches!(error, ChessError::InvalidPgn { line: 2, column: 6, .. }), "{:?}", error);
+    }
+}
@@ -1,6 +1,11 @@
+use crate::attacks;
+use crate::bitboard::Bitboard;
 use crate::board::Board;
 use crate::castling_rights::CastlingRights;
 use crate::r#move::Move;
+use crate::move_list::MoveSink;
+use crate::move_stage::MoveStage;
+use crate::square::{File, Rank, Square};
 use crate::tile::Tile;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -19,7 +24,16 @@ pub enum Color {
     Black,
 }
 
-#[derive(Debug, Clone, Copy)]
+impl Color {
+    pub fn opposite(&self) -> Color {
+        match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ChessPiece {
     pub piece_type: Piece,
     pub color: Color,
@@ -30,346 +44,168 @@ impl ChessPiece {
         ChessPiece { piece_type, color }
     }
 
-    pub fn get_possible_moves(&self, origin_tile: Tile, board: &Board, castling_rights: CastlingRights, en_passant_target: Option<Tile>) -> Vec<Move> {
-        match self.piece_type {
-            Piece::Pawn => self.get_pawn_moves(origin_tile, board, en_passant_target),
-            Piece::Knight => self.get_knight_moves(origin_tile, board),
-            Piece::Rook => self.get_rook_moves(origin_tile, board),
-            Piece::Bishop => self.get_bishop_moves(origin_tile, board),
-            Piece::Queen => self.get_queen_moves(origin_tile, board),
-            Piece::King => self.get_king_moves(origin_tile, board, castling_rights),
-        }
+    // FEN letter for the piece: uppercase for White, lowercase for Black
+    pub fn from_fen_char(c: char) -> Option<Self> {
+        let piece_type = match c.to_ascii_lowercase() {
+            'k' => Piece::King,
+            'q' => Piece::Queen,
+            'r' => Piece::Rook,
+            'b' => Piece::Bishop,
+            'n' => Piece::Knight,
+            'p' => Piece::Pawn,
+            _ => return None,
+        };
+        let color = if c.is_ascii_uppercase() { Color::White } else { Color::Black };
+        Some(ChessPiece::new(piece_type, color))
     }
 
-    fn get_pawn_moves(&self, origin_tile: Tile, board: &Board, en_passant_target: Option<Tile>) -> Vec<Move> {
-        let mut moves= Vec::new();
-        let (x, y) = origin_tile.get_coords();
-
-        let direction = if self.color == Color::White { 1 } else { -1 };
-
-        // Default forward move 1
-        let one_row_forward = (y as isize + direction) as usize;
-
-        if board.is_on_board(x, one_row_forward) {
-            let destination_tile = *board.get_tile(x, one_row_forward);
-            if !destination_tile.is_occupied() {
-                if (self.color == Color::White && y == 6) || (self.color == Color::Black && y == 1) {
-                    // Promotion
-                    let promotion = [
-                        ChessPiece::new(Piece::Queen, self.color), 
-                        ChessPiece::new(Piece::Rook, self.color), 
-                        ChessPiece::new(Piece::Bishop, self.color), 
-                        ChessPiece::new(Piece::Knight, self.color)
-                    ];
-                    for piece in promotion {
-                        let pawn_move = Move::new(origin_tile, destination_tile, *self, Some(piece));
-                        moves.push(pawn_move);
-                    }
-                } else {
-                    // Non-promotion
-                    let pawn_move: Move = Move::new(origin_tile, destination_tile, *self, None);
-                    moves.push(pawn_move);
-                }
-            }    
-        }
-
-        // Pawn can move 2 spaces forward from starting position
-        if self.color == Color::White && y == 1 || self.color == Color::Black && y == 6 {
-            let two_rows_forward = (y as isize + direction * 2) as usize;
-            let destination_tile = board.get_tile(x, two_rows_forward);
-            let one_row_forward_tile = board.get_tile(x, one_row_forward);
-            if !destination_tile.is_occupied() && !one_row_forward_tile.is_occupied() {
-                let pawn_move = Move::new(origin_tile, *destination_tile, *self, None);
-                moves.push(pawn_move);
-            }
-        } 
-
-        // Diagonal capture left
-        let new_x_left = (x as isize - 1) as usize;
-        let new_y_left = (y as isize + direction) as usize;
-        if board.is_on_board(new_x_left, new_y_left) {
-            let destination_tile = board.get_tile(new_x_left, new_y_left);
-            if let Some(piece) = destination_tile.piece {
-                if piece.color != self.color {
-                    if (self.color == Color::White && y == 6) || (self.color == Color::Black && y == 1) {
-                        // Promotion
-                        let promotion = [
-                            ChessPiece::new(Piece::Queen, self.color), 
-                            ChessPiece::new(Piece::Rook, self.color), 
-                            ChessPiece::new(Piece::Bishop, self.color), 
-                            ChessPiece::new(Piece::Knight, self.color)
-                        ];                        
-                    
-                        for piece in promotion {
-                            let pawn_move: Move = Move::new(origin_tile, *destination_tile, *self, Some(piece));
-                            moves.push(pawn_move);
-                        }
-                    } else {
-                        // Non-Promotion
-                        let pawn_move: Move = Move::new(origin_tile, *destination_tile, *self, None);
-                        moves.push(pawn_move);
-                    }
-                }
-            }
-        }
-
-        // Diagonal capture right
-        let new_x_right = (x as isize + 1) as usize;
-        let new_y_right = (y as isize + direction) as usize;
-        if board.is_on_board(new_x_right, new_y_right) {
-            let destination_tile = board.get_tile(new_x_right, new_y_right);
-            if let Some(piece) = destination_tile.piece {
-                if piece.color != self.color {
-                    if (self.color == Color::White && y == 6) || (self.color == Color::Black && y == 1) {
-                        // Promotion
-                        let promotion = [
-                            ChessPiece::new(Piece::Queen, self.color), 
-                            ChessPiece::new(Piece::Rook, self.color), 
-                            ChessPiece::new(Piece::Bishop, self.color), 
-                            ChessPiece::new(Piece::Knight, self.color)
-                        ];                        
-
-                        for piece in promotion {
-                            let pawn_move: Move = Move::new(origin_tile, *destination_tile, *self, Some(piece));
-                            moves.push(pawn_move);
-                        }
-                    } else {
-                        // Non-Promotion
-                        let pawn_move: Move = Move::new(origin_tile, *destination_tile, *self, None);
-                        moves.push(pawn_move);
-                    }
-                }
-            }
+    pub fn to_fen_char(&self) -> char {
+        let c = match self.piece_type {
+            Piece::King => 'k',
+            Piece::Queen => 'q',
+            Piece::Rook => 'r',
+            Piece::Bishop => 'b',
+            Piece::Knight => 'n',
+            Piece::Pawn => 'p',
+        };
+        match self.color {
+            Color::White => c.to_ascii_uppercase(),
+            Color::Black => c,
         }
+    }
 
-        // En passant logic
-        if let Some(en_passant_tile) = en_passant_target {
-            let (en_passant_x, en_passant_y) = en_passant_tile.get_coords();
-            if (x as isize - en_passant_x as isize).abs() == 1 && en_passant_y == (y as isize + direction).try_into().unwrap() {
-                let destination_tile = *board.get_tile(en_passant_x, en_passant_y);
-                if !destination_tile.is_occupied() {
-                    moves.push(Move::new(origin_tile, destination_tile, *self, None));
-                }
-            }
-        }
-        
+    pub fn get_possible_moves(&self, origin_tile: Tile, board: &Board, castling_rights: CastlingRights, en_passant_target: Option<Tile>) -> Vec<Move> {
+        let mut moves = Vec::new();
+        self.generate_moves(origin_tile, board, castling_rights, en_passant_target, MoveStage::All, &mut moves);
         moves
     }
 
-    fn get_knight_moves(&self, origin_tile: Tile, board: &Board) -> Vec<Move> {
-        let mut moves: Vec<Move>= Vec::new();
-        let (x, y) = origin_tile.get_coords();
-
-        // Possible L-shaped moves (8 possible moves)
-        let knight_moves = [
-            (2, 1), (2, -1), (-2, 1), (-2, -1),
-            (1, 2), (1, -2), (-1, 2), (-1, -2)
-        ];
-
-        for &(dx, dy) in &knight_moves {
-            let new_x = (x as isize + dx) as usize;
-            let new_y = (y as isize + dy) as usize;
-
-            // Check if the move is on the board
-            if board.is_on_board(new_x, new_y) {
-                let destination_tile = board.get_tile(new_x, new_y);
-
-                // If the destination tile is empty or contains an opponent's piece
-                if !destination_tile.is_occupied() || destination_tile.piece.unwrap().color != self.color {
-                    let knight_move = Move::new(origin_tile, *destination_tile, *self, None);
-                    moves.push(knight_move);
+    // Push the piece's moves for the given stage onto the list, without allocating
+    pub fn generate_moves(&self, origin_tile: Tile, board: &Board, castling_rights: CastlingRights, en_passant_target: Option<Tile>, stage: MoveStage, moves: &mut impl MoveSink) {
+        let origin = origin_tile.name;
+        let occupancy = board.occupancy();
+        match self.piece_type {
+            Piece::Pawn => self.generate_pawn_moves(origin_tile, board, en_passant_target, stage, moves),
+            Piece::Knight => self.generate_moves_to(origin_tile, board, attacks::knight_attacks(origin), stage, moves),
+            Piece::Rook => self.generate_moves_to(origin_tile, board, attacks::rook_attacks(origin, occupancy), stage, moves),
+            Piece::Bishop => self.generate_moves_to(origin_tile, board, attacks::bishop_attacks(origin, occupancy), stage, moves),
+            Piece::Queen => self.generate_moves_to(origin_tile, board, attacks::queen_attacks(origin, occupancy), stage, moves),
+            Piece::King => {
+                self.generate_moves_to(origin_tile, board, attacks::king_attacks(origin), stage, moves);
+                if stage.includes_quiets() {
+                    self.generate_castling_moves(origin_tile, board, castling_rights, moves);
                 }
             }
         }
-        moves
     }
 
-    fn get_rook_moves(&self, origin_tile: Tile, board: &Board) -> Vec<Move> {
-        let mut moves: Vec<Move>= Vec::new();
-        let (x, y) = origin_tile.get_coords();
-
-        // Horizontal and Vertical Directions
-        let directions: [(isize, isize); 4] = [
-            (1, 0),   // Right
-            (-1, 0),  // Left
-            (0, 1),   // Down
-            (0, -1),  // Up
-        ];
+    fn generate_pawn_moves(&self, origin_tile: Tile, board: &Board, en_passant_target: Option<Tile>, stage: MoveStage, moves: &mut impl MoveSink) {
+        let origin = origin_tile.name;
+        let occupancy = board.occupancy();
 
-        for (dx, dy) in directions.iter() {
-            let mut new_x = x as isize;
-            let mut new_y = y as isize;
+        let (direction, start_rank, promotion_rank) = match self.color {
+            Color::White => (1, Rank::Second, Rank::Eighth),
+            Color::Black => (-1, Rank::Seventh, Rank::First),
+        };
 
-            // Move in the current direction until hitting the edge or another piece
-            loop {
-                new_x += dx;
-                new_y += dy;
+        let mut targets = Bitboard::EMPTY;
 
-                if !board.is_on_board(new_x as usize, new_y as usize) {
-                    break;
-                }
-
-                let destination_tile = board.get_tile(new_x as usize, new_y as usize);
-
-                if destination_tile.is_occupied() {
-                    if destination_tile.piece.unwrap().color != self.color {
-                        let rook_move = Move::new(origin_tile, *destination_tile, *self, None);
-                        moves.push(rook_move);
+        // Forward one tile, and two from the starting rank, as long as nothing is in the way
+        if stage.includes_quiets() {
+            if let Some(one_forward) = origin.offset(0, direction).filter(|square| !occupancy.contains(*square)) {
+                targets.insert(one_forward);
+                if origin.rank() == start_rank {
+                    if let Some(two_forward) = one_forward.offset(0, direction).filter(|square| !occupancy.contains(*square)) {
+                        targets.insert(two_forward);
                     }
-                    break;
-                } else {
-                    let rook_move = Move::new(origin_tile, *destination_tile, *self, None);
-                    moves.push(rook_move);
                 }
             }
         }
 
-        moves
-    }
-
-    fn get_bishop_moves(&self, origin_tile: Tile, board: &Board) -> Vec<Move> {
-        let mut moves: Vec<Move>= Vec::new();
-        let (x, y) = origin_tile.get_coords();
-
-        // Diagonal Directions
-        let directions: [(isize, isize); 4] = [
-            (1, 1),    // Down-Right
-            (-1, 1),   // Down-Left
-            (1, -1),   // Up-Right
-            (-1, -1),  // Up-Left
-        ];
-
-        for (dx, dy) in directions.iter() {
-            let mut new_x = x as isize;
-            let mut new_y = y as isize;
-
-            // Move in the current direction until hitting the edge or another piece
-            loop {
-                new_x += dx;
-                new_y += dy;
-
-                if !board.is_on_board(new_x as usize, new_y as usize) {
-                    break;
+        // Diagonal captures, including onto the en passant target
+        if stage.includes_captures() {
+            let mut capturable = board.occupancy_of(self.color.opposite());
+            if let Some(en_passant_tile) = en_passant_target {
+                if !occupancy.contains(en_passant_tile.name) {
+                    capturable.insert(en_passant_tile.name);
                 }
+            }
+            targets |= attacks::pawn_attacks(self.color, origin) & capturable;
+        }
 
-                let destination_tile = board.get_tile(new_x as usize, new_y as usize);
-
-                if destination_tile.is_occupied() {
-                    if destination_tile.piece.unwrap().color != self.color {
-                        let bishop_move = Move::new(origin_tile, *destination_tile, *self, None);
-                        moves.push(bishop_move);
-                    }
-                    break;
-                } else {
-                    let bishop_move = Move::new(origin_tile, *destination_tile, *self, None);
-                    moves.push(bishop_move);
+        for target in targets {
+            let destination_tile = board.tile(target);
+            if target.rank() == promotion_rank {
+                let promotion = [
+                    ChessPiece::new(Piece::Queen, self.color), 
+                    ChessPiece::new(Piece::Rook, self.color), 
+                    ChessPiece::new(Piece::Bishop, self.color), 
+                    ChessPiece::new(Piece::Knight, self.color)
+                ];
+                for piece in promotion {
+                    moves.push(Move::new(origin_tile, destination_tile, *self, Some(piece)));
                 }
+            } else {
+                moves.push(Move::new(origin_tile, destination_tile, *self, None));
             }
         }
-
-        moves
     }
 
-    fn get_queen_moves(&self, origin_tile: Tile, board: &Board) -> Vec<Move> {
-        let mut moves: Vec<Move>= Vec::new();
-        moves.extend(self.get_rook_moves(origin_tile, board));
-        moves.extend(self.get_bishop_moves(origin_tile, board));
-        moves
+    // One move to each target tile that is not occupied by a piece of our own color, keeping
+    // only the captures or only the moves to empty tiles when generating a single stage
+    fn generate_moves_to(&self, origin_tile: Tile, board: &Board, targets: Bitboard, stage: MoveStage, moves: &mut impl MoveSink) {
+        let mut targets = targets & !board.occupancy_of(self.color);
+        match stage {
+            MoveStage::All => {}
+            MoveStage::Captures => targets &= board.occupancy_of(self.color.opposite()),
+            MoveStage::Quiets => targets &= !board.occupancy(),
+        }
+        for target in targets {
+            moves.push(Move::new(origin_tile, board.tile(target), *self, None));
+        }
     }
 
-    fn get_king_moves(&self, origin_tile: Tile, board: &Board, castling_rights: CastlingRights) -> Vec<Move> {
-        let mut moves: Vec<Move>= Vec::new();
-        let (x, y) = origin_tile.get_coords();
+    fn generate_castling_moves(&self, origin_tile: Tile, board: &Board, castling_rights: CastlingRights, moves: &mut impl MoveSink) {
+        let king = origin_tile.name;
 
-        // The King can move one square in any direction
-        let directions: [(isize, isize); 8] = [
-            (1, 0),
-            (-1, 0),
-            (0, 1),
-            (0, -1),
-            (1, 1),
-            (-1, 1),
-            (1, -1),
-            (-1, -1),
-        ];
-
-        for (dx, dy) in directions.iter() {
-            let new_x = x as isize + dx;
-            let new_y = y as isize + dy;
+        // Castling requires the king on its home rank with the castling rook on the matching side
+        // of it. The king ends on the g or c file and the rook beside it on f or d, as in Chess960.
+        // The king may not castle out of, through or into check.
+        let home_rank = if self.color == Color::White { Rank::First } else { Rank::Eighth };
+        let opponent = self.color.opposite();
+        if king.rank() != home_rank || board.is_square_attacked(king, opponent) {
+            return;
+        }
 
-            // Check if the move is on the board
-            if !board.is_on_board(new_x as usize, new_y as usize) {
+        for (king_side, king_to_file, rook_to_file) in [(true, File::G, File::F), (false, File::C, File::D)] {
+            if !castling_rights.has_right(self.color, king_side) {
                 continue;
             }
 
-            let destination_tile = board.get_tile(new_x as usize, new_y as usize);
-            if !destination_tile.is_occupied() {
-                // Empty tile, king can move here
-                let king_move = Move::new(origin_tile, *destination_tile, *self, None);
-                moves.push(king_move);
-            } else if destination_tile.piece.unwrap().color != self.color {
-                // Capture an enemy piece
-                let king_move = Move::new(origin_tile, *destination_tile, *self, None);
-                moves.push(king_move);
+            let rook = castling_rights.rook_square(self.color, king_side);
+            let is_own_rook = board.piece_at(rook).is_some_and(|piece| piece.piece_type == Piece::Rook && piece.color == self.color);
+            if !is_own_rook || (rook.file() > king.file()) != king_side {
+                continue;
             }
-        }
-
-        // Castling logic
-        match self.color {
-            Color::White => {
-                // Kingside castling
-                if castling_rights.white_king_side {
-                    let rook_tile = board.get_tile(7, y);
-                    if rook_tile.is_occupied() {
-                        let square_5 = *board.get_tile(5, y);
-                        let square_6 = *board.get_tile(6, y);
-                        if !square_5.is_occupied() && !square_6.is_occupied() {
-                            moves.push(Move::new(origin_tile, square_6, *self, None)); // Castling move
-                        }
-                    }
-                }
 
-                // Queenside castling
-                if castling_rights.white_queen_side {
-                    let rook_tile = board.get_tile(0, y);
-                    if rook_tile.is_occupied() {
-                        let square_1 = *board.get_tile(1, y);
-                        let square_2 = *board.get_tile(2, y);
-                        let square_3 = *board.get_tile(3, y);
-                        if !square_1.is_occupied() && !square_2.is_occupied() && !square_3.is_occupied() {
-                            moves.push(Move::new(origin_tile, square_2, *self, None)); // Castling move
-                        }
-                    }
-                }
+            // Every tile either piece crosses or lands on must be empty but for the two of them
+            let files = [king.file(), rook.file(), king_to_file, rook_to_file];
+            let (first, last) = (files.iter().min().unwrap().index(), files.iter().max().unwrap().index());
+            let blocked = (first..=last)
+                .map(|x| board.get_tile(x, home_rank.index()).name)
+                .any(|square| square != king && square != rook && board.piece_at(square).is_some());
+            if blocked {
+                continue;
             }
-            Color::Black => {
-                // Kingside castling
-                if castling_rights.black_king_side {
-                    let rook_tile = board.get_tile(7, y);
-                    if rook_tile.is_occupied() {
-                        let square_5 = *board.get_tile(5, y);
-                        let square_6 = *board.get_tile(6, y);
-                        if !square_5.is_occupied() && !square_6.is_occupied() {
-                            moves.push(Move::new(origin_tile, square_6, *self, None)); // Castling move
-                        }
-                    }
-                }
 
-                // Queenside castling
-                if castling_rights.black_queen_side {
-                    let rook_tile = board.get_tile(0, y);
-                    if rook_tile.is_occupied() {
-                        let square_1 = *board.get_tile(1, y);
-                        let square_2 = *board.get_tile(2, y);
-                        let square_3 = *board.get_tile(3, y);
-                        if !square_1.is_occupied() && !square_2.is_occupied() && !square_3.is_occupied() {
-                            moves.push(Move::new(origin_tile, square_2, *self, None)); // Castling move
-                        }
-                    }
-                }
+            let king_to = Square::new(king_to_file, home_rank);
+            let (from_x, to_x) = (king.file().index(), king_to_file.index());
+            let king_path = from_x.min(to_x)..=from_x.max(to_x);
+            if king_path.map(|x| board.get_tile(x, home_rank.index()).name).any(|square| board.is_square_attacked(square, opponent)) {
+                continue;
             }
-        }
 
-        moves
+            moves.push(Move::new_castling(origin_tile, board.tile(king_to), *self, rook));
+        }
     }
 }
\ No newline at end of file
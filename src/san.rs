@@ -0,0 +1,341 @@
+use crate::chess::Chess;
+use crate::error::ChessError;
+use crate::language::Language;
+use crate::pieces::{ChessPiece, Piece};
+use crate::r#move::{Move, MoveKind};
+use crate::square::{File, Rank, Square};
+
+// One way of reading the part of a SAN move before the destination square
+#[derive(Debug, Clone, Copy)]
+struct Origin {
+    piece: Option<Piece>, // None when any piece may move, as in coordinate input like "e2e4"
+    file: Option<File>,
+    rank: Option<Rank>,
+}
+
+// Standard Algebraic Notation letter for a piece, None for pawns
+pub fn piece_letter(piece: Piece) -> Option<char> {
+    Language::English.piece_letter(piece)
+}
+
+pub fn piece_from_letter(letter: char) -> Option<Piece> {
+    Language::English.piece_from_letter(letter)
+}
+
+// Write a legal move of the position in Standard Algebraic Notation, e.g. "Nbd7", "exd6",
+// "e8=Q+", "O-O-O" or "Qh4#"
+pub fn move_to_san(chess: &Chess, mv: &Move) -> String {
+    write_san(chess, mv, |piece| piece_letter(piece.piece_type))
+}
+
+// SAN with the piece letters (or figurines) given by `letter`, which returns None for pawns
+pub fn write_san(chess: &Chess, mv: &Move, letter: impl Fn(ChessPiece) -> Option<char>) -> String {
+    let mut san = String::new();
+    let from = mv.from.name;
+    let to = mv.to.name;
+
+    if mv.is_castling() {
+        san.push_str(if mv.kind() == MoveKind::KingCastle { "O-O" } else { "O-O-O" });
+    } else {
+        let is_capture = mv.kind().is_capture();
+
+        match letter(mv.piece) {
+            // Pawn captures name the file the pawn came from
+            None => {
+                if is_capture {
+                    san.push(from.file().to_char());
+                }
+            }
+            Some(letter) => {
+                san.push(letter);
+                san.push_str(&disambiguation(chess, mv));
+            }
+        }
+
+        if is_capture {
+            san.push('x');
+        }
+        san.push_str(&to.to_string());
+
+        if let Some(letter) = mv.promotion.and_then(&letter) {
+            san.push('=');
+            san.push(letter);
+        }
+    }
+
+    san.push_str(check_suffix(chess, mv));
+    san
+}
+
+// The origin file, rank or both, whichever is needed to tell the move apart from the same kind
+// of piece moving to the same square. The file is preferred when it is enough on its own.
+pub fn disambiguation(chess: &Chess, mv: &Move) -> String {
+    let from = mv.from.name;
+    let rivals: Vec<_> = chess
+        .get_legal_moves()
+        .into_iter()
+        .filter(|other| other.piece == mv.piece && other.to.name == mv.to.name && other.from.name != from)
+        .map(|other| other.from.name)
+        .collect();
+
+    if rivals.is_empty() {
+        String::new()
+    } else if rivals.iter().all(|rival| rival.file() != from.file()) {
+        from.file().to_string()
+    } else if rivals.iter().all(|rival| rival.rank() != from.rank()) {
+        from.rank().to_string()
+    } else {
+        from.to_string()
+    }
+}
+
+// "#" for a move that mates, "+" for one that checks
+pub fn check_suffix(chess: &Chess, mv: &Move) -> &'static str {
+    let mut after = chess.clone();
+    after.make_move(*mv);
+    if after.is_checkmate() {
+        "#"
+    } else if after.is_in_check() {
+        "+"
+    } else {
+        ""
+    }
+}
+
+// Resolve a move written in SAN against the legal moves of the position. Besides strict SAN this
+// reads the usual sloppy forms: check and annotation marks, "e.p." suffixes, zeros in castling,
+// promotions without "=", lowercase piece letters, an origin square as in "Ng1-f3" and plain
+// coordinates like "e2e4". A lowercase "b" is read as a pawn on the b-file when that gives a
+// legal move and as a bishop otherwise.
+pub fn parse_san(chess: &Chess, text: &str) -> Result<Move, ChessError> {
+    parse_san_with(chess, text, piece_from_letter)
+}
+
+// SAN parsing with the piece letters read by `from_letter`, which only sees uppercase letters.
+// A lowercase letter that is both a file and a piece letter is tried as a pawn's file first.
+pub fn parse_san_with(chess: &Chess, text: &str, from_letter: impl Fn(char) -> Option<Piece>) -> Result<Move, ChessError> {
+    let invalid = || ChessError::InvalidMoveNotation(text.to_string());
+    let notation = strip_annotations(text);
+    let legal_moves = chess.get_legal_moves();
+
+    if let Some(king_side) = parse_castling(&notation) {
+        let castles = legal_moves
+            .into_iter()
+            .filter(|mv| mv.is_castling() && (mv.kind() == MoveKind::KingCastle) == king_side);
+        return single_match(text, castles.collect());
+    }
+
+    // Capture and promotion marks carry no information the destination does not already give
+    let mut chars: Vec<char> = notation.chars().filter(|c| !matches!(c, 'x' | ':' | '-' | '=')).collect();
+
+    let promotion = match chars.as_slice() {
+        [.., rank, letter] if rank.is_ascii_digit() => {
+            let promotion = from_letter(letter.to_ascii_uppercase()).filter(|piece| *piece != Piece::King);
+            if promotion.is_some() {
+                chars.pop();
+            }
+            promotion
+        }
+        _ => None,
+    };
+
+    if chars.len() < 2 {
+        return Err(invalid());
+    }
+    let destination: String = chars.split_off(chars.len() - 2).into_iter().collect();
+    let destination: Square = destination.parse().map_err(|_| invalid())?;
+
+    let origins = parse_origins(&chars, &from_letter).ok_or_else(invalid)?;
+    for origin in origins {
+        let matches: Vec<Move> = legal_moves
+            .iter()
+            .filter(|mv| {
+                mv.to.name == destination
+                    && origin.piece.is_none_or(|piece| piece == mv.piece.piece_type)
+                    && origin.file.is_none_or(|file| file == mv.from.name.file())
+                    && origin.rank.is_none_or(|rank| rank == mv.from.name.rank())
+                    && promotion.is_none_or(|piece| mv.promotion.is_some_and(|promoted| promoted.piece_type == piece))
+            })
+            .copied()
+            .collect();
+        if !matches.is_empty() {
+            return single_match(text, matches);
+        }
+    }
+    Err(ChessError::IllegalMove(text.to_string()))
+}
+
+// Drop surrounding whitespace, check and mate marks, annotations like "!?" and an en passant
+// suffix, none of which change which move is meant
+fn strip_annotations(text: &str) -> String {
+    let mut notation = text.trim().trim_end_matches(['+', '#', '!', '?']).trim_end();
+    for suffix in ["e.p.", "ep"] {
+        if let Some(stripped) = notation.strip_suffix(suffix).filter(|rest| rest.ends_with(|c: char| c.is_ascii_digit() || c.is_whitespace())) {
+            notation = stripped.trim_end();
+        }
+    }
+    notation.trim_end_matches(['+', '#', '!', '?']).to_string()
+}
+
+// Some(true) for king side castling, Some(false) for queen side, in letters O or o or in zeros
+fn parse_castling(notation: &str) -> Option<bool> {
+    let castling: String = notation.chars().filter(|c| *c != '-').map(|c| if c == '0' { 'O' } else { c.to_ascii_uppercase() }).collect();
+    match castling.as_str() {
+        "OO" => Some(true),
+        "OOO" => Some(false),
+        _ => None,
+    }
+}
+
+// The readings of what comes before the destination square, most likely first
+fn parse_origins(prefix: &[char], from_letter: impl Fn(char) -> Option<Piece>) -> Option<Vec<Origin>> {
+    let (piece, rest) = match prefix {
+        [letter, rest @ ..] if letter.is_ascii_uppercase() && from_letter(*letter).is_some() => (from_letter(*letter), rest),
+        // Lowercase letters that cannot be a file can only name a piece
+        [letter, rest @ ..] if File::from_char(*letter).is_none() && from_letter(letter.to_ascii_uppercase()).is_some() => {
+            (from_letter(letter.to_ascii_uppercase()), rest)
+        }
+        _ => (None, prefix),
+    };
+
+    let (file, rank) = parse_disambiguation(rest)?;
+    let mut origins = Vec::new();
+    match piece {
+        Some(piece) => origins.push(Origin { piece: Some(piece), file, rank }),
+        // With a full origin square the piece is whatever stands there
+        None if file.is_some() && rank.is_some() => origins.push(Origin { piece: None, file, rank }),
+        None => origins.push(Origin { piece: Some(Piece::Pawn), file, rank }),
+    }
+
+    // "bxc3" or "bc3" without a pawn move to match may still be a sloppy bishop move
+    if let [letter, rest @ ..] = prefix {
+        if let (true, Some(piece)) = (letter.is_ascii_lowercase(), from_letter(letter.to_ascii_uppercase())) {
+            if let Some((file, rank)) = parse_disambiguation(rest) {
+                origins.push(Origin { piece: Some(piece), file, rank });
+            }
+        }
+    }
+    Some(origins)
+}
+
+// An optional origin file followed by an optional origin rank
+fn parse_disambiguation(chars: &[char]) -> Option<(Option<File>, Option<Rank>)> {
+    match chars {
+        [] => Some((None, None)),
+        [c] => match File::from_char(*c) {
+            Some(file) => Some((Some(file), None)),
+            None => Some((None, Some(Rank::from_char(*c)?))),
+        },
+        [file, rank] => Some((Some(File::from_char(*file)?), Some(Rank::from_char(*rank)?))),
+        _ => None,
+    }
+}
+
+fn single_match(text: &str, matches: Vec<Move>) -> Result<Move, ChessError> {
+    match matches.as_slice() {
+        [] => Err(ChessError::IllegalMove(text.to_string())),
+        [mv] => Ok(*mv),
+        _ => Err(ChessError::AmbiguousMove(text.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+    // Parse the input and write the move back as SAN, so each case reads as input -> move
+    fn parsed(fen: &str, text: &str) -> Result<String, ChessError> {
+        let chess = Chess::from_fen(fen).unwrap();
+        parse_san(&chess, text).map(|mv| move_to_san(&chess, &mv))
+    }
+
+    #[test]
+    fn strict_san() {
+        assert_eq!(parsed(START, "Nf3"), Ok(String::from("Nf3")));
+        assert_eq!(parsed(START, "e4"), Ok(String::from("e4")));
+    }
+
+    #[test]
+    fn check_and_annotation_marks() {
+        let fen = "6k1/5ppp/8/8/8/8/8/R3K3 w Q - 0 1";
+        assert_eq!(parsed(fen, "Ra8+"), Ok(String::from("Ra8#")));
+        assert_eq!(parsed(fen, "Ra8#"), Ok(String::from("Ra8#")));
+        assert_eq!(parsed(START, "Nf3+"), Ok(String::from("Nf3")));
+        assert_eq!(parsed(START, "Nf3!?"), Ok(String::from("Nf3")));
+    }
+
+    #[test]
+    fn en_passant_suffix() {
+        let fen = "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1";
+        assert_eq!(parsed(fen, "exd6 e.p."), Ok(String::from("exd6")));
+        assert_eq!(parsed(fen, "exd6e.p."), Ok(String::from("exd6")));
+        assert_eq!(parsed(fen, "exd6ep"), Ok(String::from("exd6")));
+    }
+
+    #[test]
+    fn castling_with_zeros_and_lowercase() {
+        let fen = "4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1";
+        assert_eq!(parsed(fen, "0-0"), Ok(String::from("O-O")));
+        assert_eq!(parsed(fen, "0-0-0"), Ok(String::from("O-O-O")));
+        assert_eq!(parsed(fen, "o-o"), Ok(String::from("O-O")));
+        assert_eq!(parsed(fen, "O-O-O"), Ok(String::from("O-O-O")));
+    }
+
+    #[test]
+    fn promotion_without_equals_sign() {
+        let fen = "8/P3k3/8/8/8/8/8/4K3 w - - 0 1";
+        assert_eq!(parsed(fen, "a8Q"), Ok(String::from("a8=Q")));
+        assert_eq!(parsed(fen, "a8=Q"), Ok(String::from("a8=Q")));
+        assert_eq!(parsed(fen, "a8n"), Ok(String::from("a8=N")));
+    }
+
+    #[test]
+    fn lowercase_b_reads_as_pawn_before_bishop() {
+        // Only the b2 pawn can take on c3 here
+        let fen = "4k3/8/8/8/8/2p5/1P6/2B1K3 w - - 0 1";
+        assert_eq!(parsed(fen, "bxc3"), Ok(String::from("bxc3")));
+        // With no pawn move to match, the same letter falls back to the bishop
+        assert_eq!(parsed(fen, "bd2"), Ok(String::from("Bd2")));
+        assert_eq!(parsed(fen, "Bd2"), Ok(String::from("Bd2")));
+        // When the pawn and the bishop can both capture, the pawn is preferred
+        let fen = "4k3/8/8/8/8/2p5/1P1B4/4K3 w - - 0 1";
+        assert_eq!(parsed(fen, "bxc3"), Ok(String::from("bxc3")));
+        assert_eq!(parsed(fen, "Bxc3"), Ok(String::from("Bxc3")));
+    }
+
+    #[test]
+    fn origin_square_and_coordinates() {
+        assert_eq!(parsed(START, "Ng1-f3"), Ok(String::from("Nf3")));
+        assert_eq!(parsed(START, "Ng1f3"), Ok(String::from("Nf3")));
+        assert_eq!(parsed(START, "e2e4"), Ok(String::from("e4")));
+        assert_eq!(parsed(START, "e2-e4"), Ok(String::from("e4")));
+        assert_eq!(parsed(START, "nf3"), Ok(String::from("Nf3")));
+    }
+
+    #[test]
+    fn disambiguation() {
+        let fen = "4k3/8/8/8/8/8/8/R4RK1 w - - 0 1";
+        assert_eq!(parsed(fen, "Rad1"), Ok(String::from("Rad1")));
+        assert_eq!(parsed(fen, "Rfd1"), Ok(String::from("Rfd1")));
+        assert_eq!(parsed(fen, "R1d1"), Err(ChessError::AmbiguousMove(String::from("R1d1"))));
+        assert_eq!(parsed(fen, "Rff2"), Ok(String::from("Rf2")));
+    }
+
+    #[test]
+    fn ambiguous_illegal_and_invalid_inputs_are_told_apart() {
+        let fen = "4k3/8/8/8/8/8/8/R4RK1 w - - 0 1";
+        assert_eq!(parsed(fen, "Rd1"), Err(ChessError::AmbiguousMove(String::from("Rd1"))));
+        assert_eq!(parsed(START, "e5"), Err(ChessError::IllegalMove(String::from("e5"))));
+        assert_eq!(parsed(START, "O-O"), Err(ChessError::IllegalMove(String::from("O-O"))));
+        assert_eq!(parsed(START, "e9"), Err(ChessError::InvalidMoveNotation(String::from("e9"))));
+        assert_eq!(parsed(START, "hello"), Err(ChessError::InvalidMoveNotation(String::from("hello"))));
+        assert_eq!(parsed(START, ""), Err(ChessError::InvalidMoveNotation(String::new())));
+    }
+
+    #[test]
+    fn promotion_without_piece_is_ambiguous() {
+        assert_eq!(parsed("8/P3k3/8/8/8/8/8/4K3 w - - 0 1", "a8"), Err(ChessError::AmbiguousMove(String::from("a8"))));
+    }
+}
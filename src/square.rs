@@ -0,0 +1,235 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::ChessError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum File {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+}
+
+impl File {
+    pub const ALL: [File; 8] = [File::A, File::B, File::C, File::D, File::E, File::F, File::G, File::H];
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        File::ALL.get(index).copied()
+    }
+
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'a'..='h' => File::from_index(c as usize - 'a' as usize),
+            _ => None,
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    pub fn to_char(&self) -> char {
+        (b'a' + *self as u8) as char
+    }
+}
+
+impl fmt::Display for File {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Rank {
+    First,
+    Second,
+    Third,
+    Fourth,
+    Fifth,
+    Sixth,
+    Seventh,
+    Eighth,
+}
+
+impl Rank {
+    pub const ALL: [Rank; 8] = [
+        Rank::First, Rank::Second, Rank::Third, Rank::Fourth,
+        Rank::Fifth, Rank::Sixth, Rank::Seventh, Rank::Eighth,
+    ];
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        Rank::ALL.get(index).copied()
+    }
+
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '1'..='8' => Rank::from_index(c as usize - '1' as usize),
+            _ => None,
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    pub fn to_char(&self) -> char {
+        (b'1' + *self as u8) as char
+    }
+}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+// One of the 64 squares, indexed from a1 = 0 along each rank up to h8 = 63. This is the only place
+// that converts between names, coordinates and indices, so every lookup agrees on the layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Square(u8);
+
+impl Square {
+    pub const A1: Square = Square(0);
+    pub const B1: Square = Square(1);
+    pub const C1: Square = Square(2);
+    pub const D1: Square = Square(3);
+    pub const E1: Square = Square(4);
+    pub const F1: Square = Square(5);
+    pub const G1: Square = Square(6);
+    pub const H1: Square = Square(7);
+    pub const A2: Square = Square(8);
+    pub const B2: Square = Square(9);
+    pub const C2: Square = Square(10);
+    pub const D2: Square = Square(11);
+    pub const E2: Square = Square(12);
+    pub const F2: Square = Square(13);
+    pub const G2: Square = Square(14);
+    pub const H2: Square = Square(15);
+    pub const A3: Square = Square(16);
+    pub const B3: Square = Square(17);
+    pub const C3: Square = Square(18);
+    pub const D3: Square = Square(19);
+    pub const E3: Square = Square(20);
+    pub const F3: Square = Square(21);
+    pub const G3: Square = Square(22);
+    pub const H3: Square = Square(23);
+    pub const A4: Square = Square(24);
+    pub const B4: Square = Square(25);
+    pub const C4: Square = Square(26);
+    pub const D4: Square = Square(27);
+    pub const E4: Square = Square(28);
+    pub const F4: Square = Square(29);
+    pub const G4: Square = Square(30);
+    pub const H4: Square = Square(31);
+    pub const A5: Square = Square(32);
+    pub const B5: Square = Square(33);
+    pub const C5: Square = Square(34);
+    pub const D5: Square = Square(35);
+    pub const E5: Square = Square(36);
+    pub const F5: Square = Square(37);
+    pub const G5: Square = Square(38);
+    pub const H5: Square = Square(39);
+    pub const A6: Square = Square(40);
+    pub const B6: Square = Square(41);
+    pub const C6: Square = Square(42);
+    pub const D6: Square = Square(43);
+    pub const E6: Square = Square(44);
+    pub const F6: Square = Square(45);
+    pub const G6: Square = Square(46);
+    pub const H6: Square = Square(47);
+    pub const A7: Square = Square(48);
+    pub const B7: Square = Square(49);
+    pub const C7: Square = Square(50);
+    pub const D7: Square = Square(51);
+    pub const E7: Square = Square(52);
+    pub const F7: Square = Square(53);
+    pub const G7: Square = Square(54);
+    pub const H7: Square = Square(55);
+    pub const A8: Square = Square(56);
+    pub const B8: Square = Square(57);
+    pub const C8: Square = Square(58);
+    pub const D8: Square = Square(59);
+    pub const E8: Square = Square(60);
+    pub const F8: Square = Square(61);
+    pub const G8: Square = Square(62);
+    pub const H8: Square = Square(63);
+
+    // Every square from a1 to h8, so that a bit index can be turned back into a square
+    pub const ALL: [Square; 64] = {
+        let mut squares = [Square(0); 64];
+        let mut index = 0;
+        while index < 64 {
+            squares[index] = Square(index as u8);
+            index += 1;
+        }
+        squares
+    };
+
+    pub const fn new(file: File, rank: Rank) -> Self {
+        Square(rank as u8 * 8 + file as u8)
+    }
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        Square::ALL.get(index).copied()
+    }
+
+    pub fn from_coords(x: usize, y: usize) -> Option<Self> {
+        Some(Square::new(File::from_index(x)?, Rank::from_index(y)?))
+    }
+
+    pub const fn index(&self) -> usize {
+        self.0 as usize
+    }
+
+    pub fn file(&self) -> File {
+        File::ALL[self.index() % 8]
+    }
+
+    pub fn rank(&self) -> Rank {
+        Rank::ALL[self.index() / 8]
+    }
+
+    // (file, rank) as zero-based board coordinates
+    pub fn coords(&self) -> (usize, usize) {
+        (self.file().index(), self.rank().index())
+    }
+
+    // The square the given number of files and ranks away, if it is still on the board
+    pub fn offset(&self, file_delta: isize, rank_delta: isize) -> Option<Self> {
+        let (x, y) = self.coords();
+        Square::from_coords(x.checked_add_signed(file_delta)?, y.checked_add_signed(rank_delta)?)
+    }
+
+    // Every square from a1 to h8
+    pub fn all() -> impl Iterator<Item = Square> {
+        Square::ALL.into_iter()
+    }
+
+    pub fn is_light(&self) -> bool {
+        let (x, y) = self.coords();
+        (x + y) % 2 == 1
+    }
+}
+
+impl FromStr for Square {
+    type Err = ChessError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let mut chars = name.chars();
+        match (chars.next().and_then(File::from_char), chars.next().and_then(Rank::from_char), chars.next()) {
+            (Some(file), Some(rank), None) => Ok(Square::new(file, rank)),
+            _ => Err(ChessError::InvalidSquare(name.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.file(), self.rank())
+    }
+}
@@ -0,0 +1,81 @@
+use crate::chess::Chess;
+use crate::move_list::MoveList;
+use crate::move_stage::MoveStage;
+use crate::r#move::Move;
+
+// Yields a position's legal moves in stages: every capture first, then the quiet moves. Each
+// stage is only generated once the previous one has been used up, so a search that cuts off
+// on a capture never pays for generating the quiets.
+pub struct StagedMoves<'a> {
+    chess: &'a Chess,
+    stage: Option<MoveStage>,
+    moves: MoveList,
+    index: usize,
+}
+
+impl<'a> StagedMoves<'a> {
+    pub fn new(chess: &'a Chess) -> Self {
+        let mut moves = MoveList::new();
+        chess.generate_legal_moves(MoveStage::Captures, &mut moves);
+        StagedMoves { chess, stage: Some(MoveStage::Captures), moves, index: 0 }
+    }
+
+    // The stage the next move will come from, or None once every move has been yielded
+    pub fn stage(&self) -> Option<MoveStage> {
+        self.stage
+    }
+}
+
+impl Iterator for StagedMoves<'_> {
+    type Item = Move;
+
+    fn next(&mut self) -> Option<Move> {
+        loop {
+            if let Some(&mv) = self.moves.get(self.index) {
+                self.index += 1;
+                return Some(mv);
+            }
+
+            match self.stage? {
+                MoveStage::Captures => {
+                    self.moves.clear();
+                    self.index = 0;
+                    self.chess.generate_legal_moves(MoveStage::Quiets, &mut self.moves);
+                    self.stage = Some(MoveStage::Quiets);
+                }
+                _ => {
+                    self.stage = None;
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packed_move::PackedMove;
+
+    #[test]
+    fn yields_the_legal_moves_captures_first() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        ] {
+            let chess = Chess::from_fen(fen).unwrap();
+            let staged: Vec<Move> = chess.staged_moves().collect();
+
+            let mut staged_packed: Vec<u16> = staged.iter().map(|mv| PackedMove::from(*mv).to_bits()).collect();
+            let mut legal_packed: Vec<u16> = chess.get_legal_moves().into_iter().map(|mv| PackedMove::from(mv).to_bits()).collect();
+            staged_packed.sort_unstable();
+            legal_packed.sort_unstable();
+            assert_eq!(staged_packed, legal_packed, "{}", fen);
+
+            let captures = staged.iter().take_while(|mv| mv.kind().is_capture()).count();
+            assert!(staged[captures..].iter().all(|mv| !mv.kind().is_capture()), "{}", fen);
+        }
+    }
+}
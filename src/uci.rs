@@ -0,0 +1,87 @@
+use crate::chess::Chess;
+use crate::error::ChessError;
+use crate::pieces::Piece;
+use crate::r#move::Move;
+use crate::san;
+use crate::square::Square;
+
+// Resolve a UCI move such as "e2e4" or "e7e8q" against the legal moves of the position. Castling
+// is accepted both as the king moving to its destination ("e1g1") and, as Chess960 GUIs send
+// it, as the king taking its own rook ("e1h1").
+pub fn parse_uci(chess: &Chess, text: &str) -> Result<Move, ChessError> {
+    let invalid = || ChessError::InvalidMoveNotation(text.to_string());
+    let uci = text.trim();
+    if !uci.is_ascii() || !(4..=5).contains(&uci.len()) {
+        return Err(invalid());
+    }
+
+    let from: Square = uci[0..2].parse().map_err(|_| invalid())?;
+    let to: Square = uci[2..4].parse().map_err(|_| invalid())?;
+    let promotion = match uci[4..].chars().next() {
+        Some(letter) => Some(san::piece_from_letter(letter.to_ascii_uppercase()).filter(|piece| *piece != Piece::King).ok_or_else(invalid)?),
+        None => None,
+    };
+
+    let candidates: Vec<Move> = chess
+        .get_legal_moves()
+        .into_iter()
+        .filter(|mv| {
+            mv.from.name == from
+                && (mv.to.name == to || mv.castling_rook == Some(to))
+                && mv.promotion.map(|piece| piece.piece_type) == promotion
+        })
+        .collect();
+
+    // In Chess960 a king can sometimes both step onto its castling destination and castle there,
+    // in which case the plain king move is meant, as the castle would be sent as taking the rook
+    candidates
+        .iter()
+        .find(|mv| !mv.is_castling())
+        .or(candidates.first())
+        .copied()
+        .ok_or_else(|| ChessError::IllegalMove(text.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn standard_castling_both_ways() {
+        let chess = Chess::from_fen("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1").unwrap();
+        for text in ["e1g1", "e1h1"] {
+            let mv = parse_uci(&chess, text).unwrap();
+            assert_eq!((mv.to_uci(), mv.to_uci_chess960()), (String::from("e1g1"), String::from("e1h1")));
+        }
+        assert_eq!(parse_uci(&chess, "e1a1").unwrap().to_uci(), "e1c1");
+    }
+
+    #[test]
+    fn chess960_castling_with_king_and_rook_swapping() {
+        let mut chess = Chess::from_fen("4k3/8/8/8/8/8/8/5KR1 w K - 0 1").unwrap();
+        let mv = parse_uci(&chess, "f1g1").unwrap();
+        assert!(mv.is_castling());
+        assert_eq!(mv.to_uci_chess960(), "f1g1");
+
+        let before = chess.to_fen();
+        let undo = chess.make_move(mv);
+        assert_eq!(chess.to_fen(), "4k3/8/8/8/8/8/8/5RK1 b - - 1 1");
+        assert_eq!(chess.hash(), chess.compute_hash());
+        chess.unmake_move(undo);
+        assert_eq!(chess.to_fen(), before);
+    }
+
+    #[test]
+    fn chess960_step_is_preferred_over_castling_to_the_same_square() {
+        // The king can step to g1 or castle there with the h1 rook, sent as "f1h1"
+        let chess = Chess::from_fen("4k3/8/8/8/8/8/8/5K1R w K - 0 1").unwrap();
+        assert!(!parse_uci(&chess, "f1g1").unwrap().is_castling());
+        assert!(parse_uci(&chess, "f1h1").unwrap().is_castling());
+    }
+
+    #[test]
+    fn shredder_castling_files_round_trip() {
+        let fen = "1rqbkrbn/1ppppp1p/1n6/p1N3p1/8/2P4P/PP1PPPP1/1RQBKRBN w FBfb - 0 9";
+        assert_eq!(Chess::from_fen(fen).unwrap().to_fen(), fen);
+    }
+}
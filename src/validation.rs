@@ -0,0 +1,224 @@
+use std::fmt;
+
+use crate::chess::Chess;
+use crate::pieces::{Color, Piece};
+use crate::square::{Rank, Square};
+
+// How from_fen_validated treats a position that parses but could not arise in a real game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationMode {
+    Strict,  // Reject the position on any violation
+    Lenient, // Drop bogus castling rights and en passant targets, reject anything else
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionViolation {
+    MissingKing(Color),
+    MultipleKings { color: Color, count: usize },
+    TooManyPieces { color: Color, count: usize }, // More than 16 pieces
+    TooManyPawns { color: Color, count: usize },  // More than 8 pawns
+    PawnOnBackRank(Square),                       // Pawns never stand on rank 1 or 8
+    OpponentInCheck,                              // The side that just moved left its king attacked
+    InvalidEnPassant(Square),                     // Not behind a pawn that just moved two tiles
+    InvalidCastlingRight(char),                   // King or castling rook is not on its home tile
+}
+
+impl PositionViolation {
+    // Lenient validation can fix these by dropping the offending right or target
+    pub fn is_repairable(&self) -> bool {
+        matches!(self, PositionViolation::InvalidEnPassant(_) | PositionViolation::InvalidCastlingRight(_))
+    }
+}
+
+impl fmt::Display for PositionViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PositionViolation::MissingKing(color) => write!(f, "{:?} has no king", color),
+            PositionViolation::MultipleKings { color, count } => write!(f, "{:?} has {} kings", color, count),
+            PositionViolation::TooManyPieces { color, count } => write!(f, "{:?} has {} pieces, at most 16 are allowed", color, count),
+            PositionViolation::TooManyPawns { color, count } => write!(f, "{:?} has {} pawns, at most 8 are allowed", color, count),
+            PositionViolation::PawnOnBackRank(name) => write!(f, "pawn on back rank at {}", name),
+            PositionViolation::OpponentInCheck => write!(f, "the side not to move is in check"),
+            PositionViolation::InvalidEnPassant(name) => write!(f, "en passant square {} is not behind a pawn that just moved two tiles", name),
+            PositionViolation::InvalidCastlingRight(right) => write!(f, "castling right '{}' without king and rook on their home tiles", right),
+        }
+    }
+}
+
+// Every way the position breaks the rules of chess, in board order
+pub fn find_violations(chess: &Chess) -> Vec<PositionViolation> {
+    let mut violations = Vec::new();
+    let board = &chess.board;
+
+    for color in [Color::White, Color::Black] {
+        let pieces: Vec<Piece> = board
+            .tiles()
+            .filter_map(|tile| tile.piece)
+            .filter(|piece| piece.color == color)
+            .map(|piece| piece.piece_type)
+            .collect();
+
+        let kings = pieces.iter().filter(|&&piece| piece == Piece::King).count();
+        match kings {
+            0 => violations.push(PositionViolation::MissingKing(color)),
+            1 => {}
+            count => violations.push(PositionViolation::MultipleKings { color, count }),
+        }
+
+        if pieces.len() > 16 {
+            violations.push(PositionViolation::TooManyPieces { color, count: pieces.len() });
+        }
+
+        let pawns = pieces.iter().filter(|&&piece| piece == Piece::Pawn).count();
+        if pawns > 8 {
+            violations.push(PositionViolation::TooManyPawns { color, count: pawns });
+        }
+    }
+
+    for tile in board.tiles() {
+        let rank = tile.name.rank();
+        if (rank == Rank::First || rank == Rank::Eighth) && tile.piece.is_some_and(|piece| piece.piece_type == Piece::Pawn) {
+            violations.push(PositionViolation::PawnOnBackRank(tile.name));
+        }
+    }
+
+    let opponent = chess.turn.opposite();
+    if let Some(king_tile) = board.get_king_tile(opponent) {
+        if board.is_square_attacked(king_tile.name, chess.turn) {
+            violations.push(PositionViolation::OpponentInCheck);
+        }
+    }
+
+    if let Some(target) = chess.en_passant_target {
+        if !is_valid_en_passant_target(chess, target.name) {
+            violations.push(PositionViolation::InvalidEnPassant(target.name));
+        }
+    }
+
+    for right in invalid_castling_rights(chess) {
+        violations.push(PositionViolation::InvalidCastlingRight(right));
+    }
+
+    violations
+}
+
+// The target must be the empty tile a pawn of the side that just moved skipped, with the tile it
+// started from also empty and the pawn itself one tile further on
+fn is_valid_en_passant_target(chess: &Chess, target: Square) -> bool {
+    let board = &chess.board;
+    let (target_rank, forward) = match chess.turn {
+        Color::White => (Rank::Sixth, 1),
+        Color::Black => (Rank::Third, -1),
+    };
+    let (Some(origin), Some(pawn)) = (target.offset(0, forward), target.offset(0, -forward)) else {
+        return false;
+    };
+
+    target.rank() == target_rank
+        && !board.tile(target).is_occupied()
+        && !board.tile(origin).is_occupied()
+        && board.tile(pawn).piece.is_some_and(|piece| {
+            piece.piece_type == Piece::Pawn && piece.color == chess.turn.opposite()
+        })
+}
+
+// Castling rights, as K, Q, k or q, without the king on its home rank and the castling rook on
+// its starting tile on that side of the king
+fn invalid_castling_rights(chess: &Chess) -> Vec<char> {
+    let board = &chess.board;
+    let rights = chess.castling_rights;
+
+    [(Color::White, true, 'K'), (Color::White, false, 'Q'), (Color::Black, true, 'k'), (Color::Black, false, 'q')]
+        .into_iter()
+        .filter(|(color, king_side, _)| {
+            let rook = rights.rook_square(*color, *king_side);
+            let is_rook_there = board.piece_at(rook).is_some_and(|piece| piece.piece_type == Piece::Rook && piece.color == *color);
+            let is_king_beside = board
+                .get_king_tile(*color)
+                .is_some_and(|king| king.name.rank() == rook.rank() && (rook.file() > king.name.file()) == *king_side);
+            rights.has_right(*color, *king_side) && !(is_rook_there && is_king_beside)
+        })
+        .map(|(_, _, right)| right)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ChessError;
+
+    fn violations(fen: &str) -> Vec<PositionViolation> {
+        Chess::from_fen(fen).unwrap().validate()
+    }
+
+    #[test]
+    fn start_position_is_valid() {
+        assert_eq!(violations("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"), vec![]);
+    }
+
+    #[test]
+    fn missing_king() {
+        assert_eq!(violations("8/8/8/8/8/8/8/4K3 w - - 0 1"), vec![PositionViolation::MissingKing(Color::Black)]);
+    }
+
+    #[test]
+    fn multiple_kings() {
+        assert_eq!(
+            violations("4k3/8/8/8/8/8/8/3KK3 w - - 0 1"),
+            vec![PositionViolation::MultipleKings { color: Color::White, count: 2 }]
+        );
+    }
+
+    #[test]
+    fn too_many_pieces() {
+        assert!(violations("4k3/8/8/8/8/NNNNNNNN/NNNNNNNN/4K3 w - - 0 1")
+            .contains(&PositionViolation::TooManyPieces { color: Color::White, count: 17 }));
+    }
+
+    #[test]
+    fn too_many_pawns() {
+        assert_eq!(
+            violations("4k3/8/8/8/8/P7/PPPPPPPP/4K3 w - - 0 1"),
+            vec![PositionViolation::TooManyPawns { color: Color::White, count: 9 }]
+        );
+    }
+
+    #[test]
+    fn pawn_on_back_rank() {
+        assert_eq!(violations("P3k3/8/8/8/8/8/8/4K3 w - - 0 1"), vec![PositionViolation::PawnOnBackRank(Square::A8)]);
+    }
+
+    #[test]
+    fn opponent_in_check() {
+        assert_eq!(violations("4k3/8/8/8/8/8/8/4R1K1 w - - 0 1"), vec![PositionViolation::OpponentInCheck]);
+    }
+
+    #[test]
+    fn invalid_en_passant() {
+        assert_eq!(violations("4k3/8/8/8/8/8/8/4K3 w - e6 0 1"), vec![PositionViolation::InvalidEnPassant(Square::E6)]);
+    }
+
+    #[test]
+    fn invalid_castling_right() {
+        assert_eq!(violations("4k3/8/8/8/8/8/8/4K2R w KQ - 0 1"), vec![PositionViolation::InvalidCastlingRight('Q')]);
+    }
+
+    #[test]
+    fn strict_mode_rejects_repairable_violations() {
+        let result = Chess::from_fen_validated("4k3/8/8/8/8/8/8/4K2R w KQkq e6 0 1", ValidationMode::Strict);
+        assert!(matches!(result, Err(ChessError::InvalidPosition(_))));
+    }
+
+    #[test]
+    fn lenient_mode_drops_bogus_castling_and_en_passant() {
+        let chess = Chess::from_fen_validated("4k3/8/8/8/8/8/8/4K2R w KQkq e6 0 1", ValidationMode::Lenient).unwrap();
+        assert_eq!(chess.to_fen(), "4k3/8/8/8/8/8/8/4K2R w K - 0 1");
+        assert_eq!(chess.hash(), chess.compute_hash());
+    }
+
+    #[test]
+    fn lenient_mode_still_rejects_other_violations() {
+        let result = Chess::from_fen_validated("8/8/8/8/8/8/8/4K2R w KQkq - 0 1", ValidationMode::Lenient);
+        assert_eq!(result.unwrap_err(), ChessError::InvalidPosition(vec![PositionViolation::MissingKing(Color::Black)]));
+    }
+}
@@ -0,0 +1,124 @@
+use crate::attacks;
+use crate::board::Board;
+use crate::castling_rights::CastlingRights;
+use crate::pieces::{ChessPiece, Color, Piece};
+use crate::square::{File, Square};
+use crate::tile::Tile;
+
+// Zobrist hashing: every feature of a position has a random 64-bit key and a position's hash is
+// the XOR of the keys of the features it has, so a move only has to XOR the features it changes.
+// The keys come from a fixed-seed splitmix64 generator evaluated at compile time, so hashes are
+// the same on every run and every platform.
+struct Keys {
+    pieces: [[[u64; 64]; 6]; 2], // By color, piece type and square
+    black_to_move: u64,
+    castling: [u64; 4], // White king side, white queen side, black king side, black queen side
+    en_passant: [u64; 8], // By file
+}
+
+const SEED: u64 = 0x5A0B_1257_C8E5_D3A1;
+
+const fn splitmix64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (state, z ^ (z >> 31))
+}
+
+const fn generate_keys() -> Keys {
+    let mut keys = Keys { pieces: [[[0; 64]; 6]; 2], black_to_move: 0, castling: [0; 4], en_passant: [0; 8] };
+    let mut state = SEED;
+    let mut key;
+
+    let mut color = 0;
+    while color < 2 {
+        let mut piece = 0;
+        while piece < 6 {
+            let mut square = 0;
+            while square < 64 {
+                (state, key) = splitmix64(state);
+                keys.pieces[color][piece][square] = key;
+                square += 1;
+            }
+            piece += 1;
+        }
+        color += 1;
+    }
+
+    (state, key) = splitmix64(state);
+    keys.black_to_move = key;
+
+    let mut right = 0;
+    while right < 4 {
+        (state, key) = splitmix64(state);
+        keys.castling[right] = key;
+        right += 1;
+    }
+
+    let mut file = 0;
+    while file < 8 {
+        (state, key) = splitmix64(state);
+        keys.en_passant[file] = key;
+        file += 1;
+    }
+
+    keys
+}
+
+static KEYS: Keys = generate_keys();
+
+pub fn piece(piece: ChessPiece, square: Square) -> u64 {
+    KEYS.pieces[piece.color as usize][piece.piece_type as usize][square.index()]
+}
+
+pub fn black_to_move() -> u64 {
+    KEYS.black_to_move
+}
+
+pub fn castling(castling_rights: CastlingRights) -> u64 {
+    let rights = [
+        castling_rights.white_king_side,
+        castling_rights.white_queen_side,
+        castling_rights.black_king_side,
+        castling_rights.black_queen_side,
+    ];
+    rights
+        .iter()
+        .zip(KEYS.castling)
+        .filter(|(has_right, _)| **has_right)
+        .fold(0, |hash, (_, key)| hash ^ key)
+}
+
+pub fn en_passant(file: File) -> u64 {
+    KEYS.en_passant[file.index()]
+}
+
+// The en passant file only counts when a pawn of the side to move could actually capture onto
+// the target, so positions that only differ by an unusable target hash the same
+pub fn en_passant_target(board: &Board, turn: Color, en_passant_target: Option<Tile>) -> u64 {
+    match en_passant_target {
+        Some(target) if can_capture_en_passant(board, turn, target.name) => en_passant(target.name.file()),
+        _ => 0,
+    }
+}
+
+fn can_capture_en_passant(board: &Board, turn: Color, target: Square) -> bool {
+    // The capturing pawns are the ones an enemy pawn on the target square would attack
+    !(attacks::pawn_attacks(turn.opposite(), target) & board.pieces(Piece::Pawn, turn)).is_empty()
+}
+
+// Hash a position from scratch
+pub fn hash(board: &Board, turn: Color, castling_rights: CastlingRights, en_passant_target: Option<Tile>) -> u64 {
+    let mut hash = board
+        .tiles()
+        .filter_map(|tile| tile.piece.map(|piece_on_tile| piece(piece_on_tile, tile.name)))
+        .fold(0, |hash, key| hash ^ key);
+
+    if turn == Color::Black {
+        hash ^= black_to_move();
+    }
+    hash ^= castling(castling_rights);
+    hash ^= self::en_passant_target(board, turn, en_passant_target);
+    hash
+}
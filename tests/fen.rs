@@ -0,0 +1,52 @@
+use cratechess::chess::Chess;
+
+// The perft suite positions, which between them cover castling rights, en passant targets,
+// promotions and non-trivial clocks
+const FENS: [&str; 7] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+];
+
+fn assert_round_trip(chess: &Chess) {
+    let fen = chess.to_fen();
+    let parsed = Chess::from_fen(&fen).unwrap_or_else(|error| panic!("{} does not parse: {}", fen, error));
+    assert_eq!(parsed.to_fen(), fen);
+    assert_eq!(parsed.hash(), chess.hash(), "{}", fen);
+}
+
+fn round_trip_tree(chess: &mut Chess, depth: u32) {
+    assert_round_trip(chess);
+    if depth == 0 {
+        return;
+    }
+    for mv in chess.get_legal_moves() {
+        let undo = chess.make_move(mv);
+        round_trip_tree(chess, depth - 1);
+        chess.unmake_move(undo);
+    }
+}
+
+#[test]
+fn suite_fens_round_trip_exactly() {
+    for fen in FENS {
+        assert_eq!(Chess::from_fen(fen).unwrap().to_fen(), fen);
+    }
+}
+
+#[test]
+fn positions_reached_by_make_move_round_trip() {
+    for fen in FENS {
+        round_trip_tree(&mut Chess::from_fen(fen).unwrap(), 2);
+    }
+}
+
+#[test]
+fn clocks_past_255_round_trip() {
+    let fen = "4k3/8/8/8/8/8/8/4K3 w - - 300 200";
+    assert_eq!(Chess::from_fen(fen).unwrap().to_fen(), fen);
+}
@@ -0,0 +1,121 @@
+use cratechess::chess::Chess;
+use cratechess::packed_move::PackedMove;
+use cratechess::r#move::Move;
+use cratechess::perft::PerftStats;
+use cratechess::perft_cache::PerftCache;
+
+const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+const POSITION_3: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+const POSITION_4: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+const POSITION_4_MIRRORED: &str = "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1";
+const POSITION_5: &str = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
+const POSITION_6: &str = "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10";
+
+// Expected counts from the Chess Programming Wiki's perft results page, in the order nodes,
+// captures, en passant, castles, promotions, checks, discovered checks, double checks, checkmates
+fn stats(counts: [u64; 9]) -> PerftStats {
+    let [nodes, captures, en_passants, castles, promotions, checks, discovered_checks, double_checks, checkmates] = counts;
+    PerftStats { nodes, captures, en_passants, castles, promotions, checks, discovered_checks, double_checks, checkmates }
+}
+
+fn assert_perft(fen: &str, expected: &[[u64; 9]]) {
+    let chess = Chess::from_fen(fen).unwrap();
+    for (depth, counts) in (1..).zip(expected) {
+        assert_eq!(chess.perft(depth), stats(*counts), "{} at depth {}", fen, depth);
+    }
+}
+
+fn assert_nodes(fen: &str, expected: &[u64]) {
+    let chess = Chess::from_fen(fen).unwrap();
+    for (depth, nodes) in (1..).zip(expected) {
+        assert_eq!(chess.perft(depth).nodes, *nodes, "{} at depth {}", fen, depth);
+    }
+}
+
+#[test]
+fn start_position() {
+    assert_perft(START, &[
+        [20, 0, 0, 0, 0, 0, 0, 0, 0],
+        [400, 0, 0, 0, 0, 0, 0, 0, 0],
+        [8902, 34, 0, 0, 0, 12, 0, 0, 0],
+        [197281, 1576, 0, 0, 0, 469, 0, 0, 8],
+    ]);
+}
+
+#[test]
+fn kiwipete() {
+    assert_perft(KIWIPETE, &[
+        [48, 8, 0, 2, 0, 0, 0, 0, 0],
+        [2039, 351, 1, 91, 0, 3, 0, 0, 0],
+        [97862, 17102, 45, 3162, 0, 993, 0, 0, 1],
+    ]);
+}
+
+#[test]
+fn position_3() {
+    assert_perft(POSITION_3, &[
+        [14, 1, 0, 0, 0, 2, 0, 0, 0],
+        [191, 14, 0, 0, 0, 10, 0, 0, 0],
+        [2812, 209, 2, 0, 0, 267, 3, 0, 0],
+        [43238, 3348, 123, 0, 0, 1680, 106, 0, 17],
+    ]);
+}
+
+#[test]
+fn position_4() {
+    let expected = [
+        [6, 0, 0, 0, 0, 0, 0, 0, 0],
+        [264, 87, 0, 6, 48, 10, 0, 0, 0],
+        [9467, 1021, 4, 0, 120, 38, 2, 0, 22],
+    ];
+    assert_perft(POSITION_4, &expected);
+    assert_perft(POSITION_4_MIRRORED, &expected);
+}
+
+#[test]
+fn position_5() {
+    assert_nodes(POSITION_5, &[44, 1486, 62379]);
+}
+
+#[test]
+fn position_6() {
+    assert_nodes(POSITION_6, &[46, 2079, 89890]);
+}
+
+// Chess960 positions from the Chess Programming Wiki, with castling rights as Shredder-FEN files
+#[test]
+fn chess960() {
+    assert_nodes("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9", &[21, 528, 12189, 326672]);
+    assert_nodes("2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9", &[21, 807, 18002]);
+    assert_nodes("b1q1rrkb/pppppppp/3nn3/8/P7/1PPP4/4PPPP/BQNNRKRB w GE - 1 9", &[20, 479, 10471]);
+    assert_nodes("1rqbkrbn/1ppppp1p/1n6/p1N3p1/8/2P4P/PP1PPPP1/1RQBKRBN w FBfb - 0 9", &[29, 502, 14569, 287739]);
+}
+
+#[test]
+fn divide_sums_to_perft() {
+    let chess = Chess::from_fen(KIWIPETE).unwrap();
+    let divide = chess.perft_divide(2);
+
+    assert_eq!(divide.len(), 48);
+    let total = divide.iter().fold(PerftStats::default(), |total, (_, stats)| total + *stats);
+    assert_eq!(total, chess.perft(2));
+}
+
+#[test]
+fn parallel_matches_serial() {
+    for fen in [START, KIWIPETE, POSITION_3, POSITION_4, POSITION_5, POSITION_6] {
+        let chess = Chess::from_fen(fen).unwrap();
+        let serial = chess.perft(3);
+
+        assert_eq!(chess.perft_parallel(3, 4, None), serial, "{}", fen);
+        assert_eq!(chess.perft_parallel(3, 0, Some(&PerftCache::new(1 << 16))), serial, "{}", fen);
+        // A tiny cache keeps overwriting its slots, which must not change the result
+        assert_eq!(chess.perft_parallel(3, 3, Some(&PerftCache::new(64))), serial, "{}", fen);
+
+        let divide = |results: Vec<(Move, PerftStats)>| -> Vec<_> {
+            results.into_iter().map(|(mv, stats)| (PackedMove::from(mv), stats)).collect()
+        };
+        assert_eq!(divide(chess.perft_divide_parallel(3, 4, None)), divide(chess.perft_divide(3)), "{}", fen);
+    }
+}